@@ -1,3 +1,53 @@
+bitflags! {
+    pub struct ConnectFlags: u32 {
+        /// Open a read-only connection
+        const RO = 1;
+        /// Don't try to resolve URI aliases
+        const NO_ALIASES = 2;
+    }
+}
+
+/// A kind of credential libvirt can ask for while opening a
+/// connection with `Connect::open_auth()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectCredentialType {
+    /// Identity to act as
+    Username = 1,
+    /// Identity to authorize as
+    Authname = 2,
+    /// RFC 1766 languages, comma separated
+    Language = 3,
+    /// Client supplied nonce
+    Cnonce = 4,
+    /// Passphrase secret
+    Passphrase = 5,
+    /// Challenge response, shown while typing
+    EchoPrompt = 6,
+    /// Challenge response, hidden while typing
+    NoEchoPrompt = 7,
+    /// Authentication realm
+    Realm = 8,
+    /// Externally managed credential
+    External = 9,
+}
+
+impl ConnectCredentialType {
+    pub fn from_int(n: i32) -> Option<Self> {
+        match n {
+            1 => Some(ConnectCredentialType::Username),
+            2 => Some(ConnectCredentialType::Authname),
+            3 => Some(ConnectCredentialType::Language),
+            4 => Some(ConnectCredentialType::Cnonce),
+            5 => Some(ConnectCredentialType::Passphrase),
+            6 => Some(ConnectCredentialType::EchoPrompt),
+            7 => Some(ConnectCredentialType::NoEchoPrompt),
+            8 => Some(ConnectCredentialType::Realm),
+            9 => Some(ConnectCredentialType::External),
+            _ => None,
+        }
+    }
+}
+
 bitflags! {
     pub struct ListAllDomainsFlags: u32 {
         const ACTIVE = 1;
@@ -19,24 +69,66 @@ bitflags! {
 
 bitflags! {
     pub struct ListAllStoragePoolsFlags: u32 {
-        const VIR_CONNECT_LIST_STORAGE_POOLS_INACTIVE = 1;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_ACTIVE = 2;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_PERSISTENT = 4;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_TRANSIENT = 8;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_AUTOSTART = 16;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_NO_AUTOSTART = 32 	;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_DIR = 64;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_FS = 128;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_NETFS = 256;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_LOGICAL = 512;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_DISK = 1024;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_ISCSI = 2048;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_SCSI = 4096;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_MPATH = 8192;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_RBD = 16384;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_SHEEPDOG = 32768;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_GLUSTER = 65536;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_ZFS = 131072;
-        const VIR_CONNECT_LIST_STORAGE_POOLS_VSTORAGE = 262144;
+        const INACTIVE = 1;
+        const ACTIVE = 2;
+        const PERSISTENT = 4;
+        const TRANSIENT = 8;
+        const AUTOSTART = 16;
+        const NO_AUTOSTART = 32;
+        const DIR = 64;
+        const FS = 128;
+        const NETFS = 256;
+        const LOGICAL = 512;
+        const DISK = 1024;
+        const ISCSI = 2048;
+        const SCSI = 4096;
+        const MPATH = 8192;
+        const RBD = 16384;
+        const SHEEPDOG = 32768;
+        const GLUSTER = 65536;
+        const ZFS = 131072;
+        const VSTORAGE = 262144;
     }
 }
+
+/// The old names of the `ListAllStoragePoolsFlags` constants.
+impl ListAllStoragePoolsFlags {
+    #[deprecated(note = "use ListAllStoragePoolsFlags::INACTIVE")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_INACTIVE: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::INACTIVE;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::ACTIVE")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_ACTIVE: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::ACTIVE;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::PERSISTENT")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_PERSISTENT: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::PERSISTENT;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::TRANSIENT")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_TRANSIENT: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::TRANSIENT;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::AUTOSTART")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_AUTOSTART: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::AUTOSTART;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::NO_AUTOSTART")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_NO_AUTOSTART: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::NO_AUTOSTART;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::DIR")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_DIR: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::DIR;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::FS")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_FS: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::FS;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::NETFS")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_NETFS: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::NETFS;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::LOGICAL")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_LOGICAL: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::LOGICAL;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::DISK")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_DISK: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::DISK;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::ISCSI")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_ISCSI: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::ISCSI;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::SCSI")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_SCSI: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::SCSI;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::MPATH")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_MPATH: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::MPATH;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::RBD")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_RBD: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::RBD;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::SHEEPDOG")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_SHEEPDOG: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::SHEEPDOG;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::GLUSTER")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_GLUSTER: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::GLUSTER;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::ZFS")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_ZFS: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::ZFS;
+    #[deprecated(note = "use ListAllStoragePoolsFlags::VSTORAGE")]
+    pub const VIR_CONNECT_LIST_STORAGE_POOLS_VSTORAGE: ListAllStoragePoolsFlags = ListAllStoragePoolsFlags::VSTORAGE;
+}
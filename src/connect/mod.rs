@@ -2,17 +2,203 @@ extern crate libc;
 extern crate libvirt_sys as sys;
 
 use domain::Domain;
+use domain::flags::{DomainSaveImageXmlFlags, DomainSaveRestoreFlags};
+use domain::state::DomainLifecycleEvent;
 use error::Error;
-use std::{ptr, mem};
+use handle::Borrowed;
+use event::EventRegistration;
+use secret::Secret;
+use secret::flags::{SecretEvent, SecretEventId, SecretLifecycleEvent};
+use storage_pool::StoragePool;
+use storage_pool::flags::{StoragePoolEvent, StoragePoolEventId, StoragePoolLifecycleEvent};
+use storage_pool::xml::{parse_pool_sources, PoolSource, StoragePoolCapabilities};
+use std::ffi::CString;
+use std::panic::{self, AssertUnwindSafe};
+use std::{fmt, ptr, mem};
 
 pub mod flags;
 use connect::flags::*;
 
+const VIR_DOMAIN_EVENT_ID_LIFECYCLE: libc::c_int = 0;
+
+/// The security model used by the host, see
+/// `Connect::get_security_model()`.
+#[derive(Clone, Debug)]
+pub struct SecurityModel {
+    /// Security model string, such as "selinux" or "apparmor".
+    pub model: String,
+    /// Domain of interpretation.
+    pub doi: String,
+}
+
+impl SecurityModel {
+    pub fn from_ptr(ptr: sys::virSecurityModelPtr) -> SecurityModel {
+        unsafe {
+            SecurityModel {
+                model: c_chars_to_string!((*ptr).model.as_ptr(), nofree),
+                doi: c_chars_to_string!((*ptr).doi.as_ptr(), nofree),
+            }
+        }
+    }
+}
+
+/// A credential requested by libvirt while opening a connection, see
+/// `ConnectAuth`.
+#[derive(Clone, Debug)]
+pub struct ConnectCredential {
+    pub cred_type: ConnectCredentialType,
+    /// Prompt to show to the user.
+    pub prompt: String,
+    /// Additional challenge to show, if any.
+    pub challenge: Option<String>,
+    /// Optional default answer.
+    pub def_result: Option<String>,
+    /// The answer, to be filled in by the callback. `None` sends an
+    /// empty answer.
+    pub result: Option<String>,
+}
+
+/// Credentials handling for `Connect::open_auth()`.
+///
+/// ```no_run
+/// # use new_libvirt::connect::{Connect, ConnectAuth};
+/// # use new_libvirt::connect::flags::{ConnectCredentialType, ConnectFlags};
+/// let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname,
+///                                      ConnectCredentialType::Passphrase],
+///                                 |creds| {
+///     for cred in creds.iter_mut() {
+///         cred.result = match cred.cred_type {
+///             ConnectCredentialType::Authname => Some("admin".to_string()),
+///             ConnectCredentialType::Passphrase => Some("secret".to_string()),
+///             _ => None,
+///         };
+///     }
+/// });
+/// let conn = Connect::open_auth("qemu+tcp://host/system", &mut auth, ConnectFlags::empty()).unwrap();
+/// ```
+pub struct ConnectAuth {
+    /// Credential types the callback can answer.
+    pub creds: Vec<ConnectCredentialType>,
+    callback: Box<dyn FnMut(&mut [ConnectCredential])>,
+}
+
+impl ConnectAuth {
+    /// `callback` is called with the credentials libvirt needs, all of
+    /// them one of the `creds` types, and fills in their `result`.
+    pub fn new<F>(creds: Vec<ConnectCredentialType>, callback: F) -> ConnectAuth
+        where F: FnMut(&mut [ConnectCredential]) + 'static
+    {
+        ConnectAuth {
+            creds: creds,
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for ConnectAuth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConnectAuth").field("creds", &self.creds).finish()
+    }
+}
+
+/// Answer the credential prompts of `virConnectOpenAuth()` through the
+/// `ConnectAuth` in `cbdata`.
+unsafe extern "C" fn auth_callback(cred: sys::virConnectCredentialPtr,
+                                   ncred: libc::c_uint,
+                                   cbdata: *mut libc::c_void)
+                                   -> libc::c_int {
+    // Unwinding into libvirt is undefined behaviour, so a panic in the
+    // user's callback fails the authentication instead.
+    match panic::catch_unwind(AssertUnwindSafe(|| fill_credentials(cred, ncred, cbdata))) {
+        Ok(ret) => ret,
+        Err(_) => -1,
+    }
+}
+
+unsafe fn fill_credentials(cred: sys::virConnectCredentialPtr,
+                           ncred: libc::c_uint,
+                           cbdata: *mut libc::c_void)
+                           -> libc::c_int {
+    let auth = &mut *(cbdata as *mut ConnectAuth);
+    let mut creds: Vec<ConnectCredential> = Vec::new();
+    for x in 0..ncred as isize {
+        let c = &*cred.offset(x);
+        let cred_type = match ConnectCredentialType::from_int(c.type_ as i32) {
+            Some(t) => t,
+            None => return -1,
+        };
+        creds.push(ConnectCredential {
+            cred_type: cred_type,
+            prompt: if c.prompt.is_null() { String::new() } else { c_chars_to_string!(c.prompt, nofree) },
+            challenge: if c.challenge.is_null() { None } else { Some(c_chars_to_string!(c.challenge, nofree)) },
+            def_result: if c.defresult.is_null() { None } else { Some(c_chars_to_string!(c.defresult, nofree)) },
+            result: None,
+        });
+    }
+    (auth.callback)(&mut creds);
+    // Check every answer before handing any of them to libvirt, so
+    // nothing is allocated when one of them can't be passed to C.
+    let mut results = Vec::new();
+    for c in creds {
+        match c.result.map(CString::new) {
+            Some(Ok(r)) => results.push(Some(r)),
+            Some(Err(_)) => return -1,
+            None => results.push(None),
+        }
+    }
+    for (x, r) in results.into_iter().enumerate() {
+        let out = &mut *cred.offset(x as isize);
+        // libvirt frees the results with free(), so they have to be
+        // allocated with malloc().
+        match r {
+            Some(r) => {
+                out.result = libc::strdup(r.as_ptr());
+                out.resultlen = r.as_bytes().len() as libc::c_uint;
+            }
+            None => {
+                out.result = ptr::null_mut();
+                out.resultlen = 0;
+            }
+        }
+    }
+    0
+}
+
 #[derive(Debug)]
 pub struct Connect {
     ptr: Option<sys::virConnectPtr>,
 }
 
+impl Drop for Connect {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.close() {
+                panic!("Unable to drop memory for Connect, code {}, message: {}", e.code, e.message)
+            }
+        }
+    }
+}
+
+impl Clone for Connect {
+    /// Take a new reference to the same object.
+    fn clone(&self) -> Connect {
+        unsafe {
+            if sys::virConnectRef(self.as_ptr()) == -1 {
+                let e = Error::last_error();
+                panic!("Unable to add reference to Connect, code {}, message: {}", e.code, e.message)
+            }
+        }
+        Connect::new(self.as_ptr())
+    }
+}
+
+// libvirt connections lock themselves around every API call and are
+// reference counted atomically, so a connection may be used from
+// several threads at once. Errors are kept per thread, see
+// `Error::last_error()`.
+unsafe impl Send for Connect {}
+unsafe impl Sync for Connect {}
+
 impl Connect {
     pub fn as_ptr(&self) -> sys::virConnectPtr {
         self.ptr.unwrap()
@@ -42,6 +228,55 @@ impl Connect {
         }
     }
 
+    /// Open a connection to `uri`, calling back into `auth` to answer
+    /// SASL or SSH credential prompts.
+    pub fn open_auth(uri: &str, auth: &mut ConnectAuth, flags: ConnectFlags) -> Result<Connect, Error> {
+        let uri = CString::new(uri).unwrap();
+        let mut cred_types: Vec<libc::c_int> = auth.creds.iter().map(|&t| t as libc::c_int).collect();
+        let mut sys_auth = sys::virConnectAuth {
+            credtype: cred_types.as_mut_ptr(),
+            ncredtype: cred_types.len() as libc::c_uint,
+            cb: Some(auth_callback),
+            cbdata: auth as *mut ConnectAuth as *mut libc::c_void,
+        };
+        unsafe {
+            let c = sys::virConnectOpenAuth(uri.as_ptr(), &mut sys_auth, flags.bits() as libc::c_uint);
+            if c.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(Connect::new(c));
+        }
+    }
+
+    /// Release this reference to the connection, same as dropping it.
+    /// Returns the number of references left; the connection is closed
+    /// once that is 0.
+    pub fn close(&mut self) -> Result<i32, Error> {
+        unsafe {
+            let ret = sys::virConnectClose(self.as_ptr());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            self.ptr = None;
+            return Ok(ret as i32);
+        }
+    }
+
+    /// The last error raised on this connection, by any thread.
+    ///
+    /// This isn't synchronized with other threads using the
+    /// connection; prefer the per-thread `Error::last_error()`, which
+    /// every method of this crate already reports.
+    pub fn get_last_error(&self) -> Option<Error> {
+        unsafe {
+            let ptr = sys::virConnGetLastError(self.as_ptr());
+            if ptr.is_null() {
+                return None;
+            }
+            return Some(Error::from_ptr(ptr));
+        }
+    }
+
     pub fn list_all_domains(&self, flags: Option<ListAllDomainsFlags>)-> Result<Vec<Domain>, Error> {
         let flags_value = flags.and_then(|f| Some(f.bits())).unwrap_or(0);
 
@@ -95,4 +330,406 @@ impl Connect {
             return Ok(array);
         }
     }
+
+    pub fn list_all_storage_pools(&self, flags: Option<ListAllStoragePoolsFlags>) -> Result<Vec<StoragePool>, Error> {
+        let flags_value = flags.map_or(0, |f| f.bits());
+
+        unsafe {
+            let mut pools: *mut sys::virStoragePoolPtr = ptr::null_mut();
+            let size = sys::virConnectListAllStoragePools(self.as_ptr(), &mut pools, flags_value as libc::c_uint);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<StoragePool> = Vec::new();
+            for x in 0..size as isize {
+                array.push(StoragePool::new(*pools.offset(x)));
+            }
+            libc::free(pools as *mut libc::c_void);
+
+            return Ok(array);
+        }
+    }
+
+    /// Number of active storage pools.
+    pub fn num_of_storage_pools(&self) -> Result<u32, Error> {
+        unsafe {
+            let ret = sys::virConnectNumOfStoragePools(self.as_ptr());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(ret as u32);
+        }
+    }
+
+    /// Number of inactive storage pools.
+    pub fn num_of_defined_storage_pools(&self) -> Result<u32, Error> {
+        unsafe {
+            let ret = sys::virConnectNumOfDefinedStoragePools(self.as_ptr());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(ret as u32);
+        }
+    }
+
+    /// Names of the active storage pools.
+    pub fn list_storage_pools(&self) -> Result<Vec<String>, Error> {
+        let max = self.num_of_storage_pools()?;
+        unsafe {
+            let mut names: Vec<*mut libc::c_char> = vec![ptr::null_mut(); max as usize];
+            let size = sys::virConnectListStoragePools(self.as_ptr(), names.as_mut_ptr(), max as libc::c_int);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<String> = Vec::new();
+            for x in 0..size as usize {
+                array.push(c_chars_to_string!(names[x]));
+            }
+            return Ok(array);
+        }
+    }
+
+    /// Names of the inactive storage pools.
+    pub fn list_defined_storage_pools(&self) -> Result<Vec<String>, Error> {
+        let max = self.num_of_defined_storage_pools()?;
+        unsafe {
+            let mut names: Vec<*mut libc::c_char> = vec![ptr::null_mut(); max as usize];
+            let size = sys::virConnectListDefinedStoragePools(self.as_ptr(), names.as_mut_ptr(), max as libc::c_int);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<String> = Vec::new();
+            for x in 0..size as usize {
+                array.push(c_chars_to_string!(names[x]));
+            }
+            return Ok(array);
+        }
+    }
+
+    /// Probe the host for sources which could back a pool of type
+    /// `pool_type`, such as LVM volume groups for "logical" or NFS
+    /// exports for "netfs". Network pool types need `src_spec`, a
+    /// `<source>` document naming the host to query.
+    pub fn find_storage_pool_sources(&self, pool_type: &str, src_spec: Option<&str>) -> Result<Vec<PoolSource>, Error> {
+        let pool_type = CString::new(pool_type).unwrap();
+        let src_spec = src_spec.map(|s| CString::new(s).unwrap());
+        let xml = unsafe {
+            let xml = sys::virConnectFindStoragePoolSources(self.as_ptr(),
+                                                            pool_type.as_ptr(),
+                                                            src_spec.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                                                            0);
+            if xml.is_null() {
+                return Err(Error::last_error());
+            }
+            c_chars_to_string!(xml)
+        };
+        parse_pool_sources(&xml)
+    }
+
+    /// Pool types and volume formats supported by the host.
+    pub fn get_storage_pool_capabilities(&self) -> Result<StoragePoolCapabilities, Error> {
+        let xml = unsafe {
+            let xml = sys::virConnectGetStoragePoolCapabilities(self.as_ptr(), 0);
+            if xml.is_null() {
+                return Err(Error::last_error());
+            }
+            c_chars_to_string!(xml)
+        };
+        StoragePoolCapabilities::from_xml(&xml)
+    }
+
+    /// Provide an XML description of the domain stored in the saved
+    /// state image at `file`.
+    pub fn save_image_get_xml_desc(&self, file: &str, flags: DomainSaveImageXmlFlags) -> Result<String, Error> {
+        unsafe {
+            let xml = sys::virDomainSaveImageGetXMLDesc(self.as_ptr(), string_to_c_chars!(file), flags.bits());
+            if xml.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(xml));
+        }
+    }
+
+    /// Replace the XML description stored in the saved state image at
+    /// `file`. `dxml` must only differ from the original in
+    /// host-specific portions, such as disk paths.
+    pub fn save_image_define_xml(&self, file: &str, dxml: &str, flags: DomainSaveRestoreFlags) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainSaveImageDefineXML(self.as_ptr(),
+                                                       string_to_c_chars!(file),
+                                                       string_to_c_chars!(dxml),
+                                                       flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Extract the security model of the host.
+    pub fn get_security_model(&self) -> Result<SecurityModel, Error> {
+        unsafe {
+            let mut secmodel: sys::virSecurityModel = mem::zeroed();
+            if sys::virNodeGetSecurityModel(self.as_ptr(), &mut secmodel) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(SecurityModel::from_ptr(&mut secmodel));
+        }
+    }
+
+    /// Register a callback for domain lifecycle events, optionally
+    /// restricted to the domain `dom`.
+    ///
+    /// An event loop must be registered and run, see
+    /// `event::event_register_default_impl()`. The callback stays
+    /// registered until the returned `EventRegistration` is dropped.
+    /// It runs on the event loop thread, so it must be `Send`.
+    pub fn domain_event_lifecycle_register<F>(&self, dom: Option<&Domain>, callback: F) -> Result<EventRegistration, Error>
+        where F: FnMut(&Connect, &Domain, DomainLifecycleEvent) + Send + 'static
+    {
+        unsafe extern "C" fn event_callback<F>(conn: sys::virConnectPtr,
+                                               dom: sys::virDomainPtr,
+                                               event: libc::c_int,
+                                               detail: libc::c_int,
+                                               opaque: *mut libc::c_void)
+                                               -> libc::c_int
+            where F: FnMut(&Connect, &Domain, DomainLifecycleEvent)
+        {
+            let f = opaque as *mut F;
+            // Both handles are borrowed from libvirt for the duration of
+            // the callback.
+            let conn = Borrowed::new(Connect::new(conn));
+            let dom = Borrowed::new(Domain::new(dom));
+            // Unwinding into libvirt's event loop is undefined
+            // behaviour, so a panic in the callback is dropped here.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                (*f)(&conn, &dom, DomainLifecycleEvent::from_int(event as i32, detail as i32))
+            }));
+            0
+        }
+
+        unsafe extern "C" fn free_callback<F>(opaque: *mut libc::c_void) {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(opaque as *mut F))));
+        }
+
+        let data = Box::into_raw(Box::new(callback));
+        unsafe {
+            let cb: unsafe extern "C" fn(sys::virConnectPtr, sys::virDomainPtr, libc::c_int, libc::c_int, *mut libc::c_void) -> libc::c_int = event_callback::<F>;
+            let ret = sys::virConnectDomainEventRegisterAny(self.as_ptr(),
+                                                            dom.map_or(ptr::null_mut(), |d| d.as_ptr()),
+                                                            VIR_DOMAIN_EVENT_ID_LIFECYCLE,
+                                                            Some(mem::transmute(cb)),
+                                                            data as *mut libc::c_void,
+                                                            Some(free_callback::<F>));
+            if ret == -1 {
+                drop(Box::from_raw(data));
+                return Err(Error::last_error());
+            }
+            return Ok(EventRegistration::new(self.as_ptr(), ret as i32, sys::virConnectDomainEventDeregisterAny));
+        }
+    }
+
+    /// Register a callback for storage pool events of type
+    /// `event_id`, optionally restricted to the pool `pool`.
+    ///
+    /// An event loop must be registered and run, see
+    /// `event::event_register_default_impl()`. The callback stays
+    /// registered until the returned `EventRegistration` is dropped.
+    pub fn storage_pool_event_register_any<F>(&self,
+                                              pool: Option<&StoragePool>,
+                                              event_id: StoragePoolEventId,
+                                              callback: F)
+                                              -> Result<EventRegistration, Error>
+        where F: FnMut(&Connect, &StoragePool, StoragePoolEvent) + Send + 'static
+    {
+        unsafe fn dispatch<F>(conn: sys::virConnectPtr, pool: sys::virStoragePoolPtr, event: StoragePoolEvent, opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &StoragePool, StoragePoolEvent)
+        {
+            let f = opaque as *mut F;
+            // Both handles are borrowed from libvirt for the duration of
+            // the callback.
+            let conn = Borrowed::new(Connect::new(conn));
+            let pool = Borrowed::new(StoragePool::new(pool));
+            // Unwinding into libvirt's event loop is undefined
+            // behaviour, so a panic in the callback is dropped here.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| (*f)(&conn, &pool, event)));
+        }
+
+        unsafe extern "C" fn lifecycle_callback<F>(conn: sys::virConnectPtr,
+                                                   pool: sys::virStoragePoolPtr,
+                                                   event: libc::c_int,
+                                                   _detail: libc::c_int,
+                                                   opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &StoragePool, StoragePoolEvent)
+        {
+            let event = StoragePoolEvent::Lifecycle(StoragePoolLifecycleEvent::from_int(event as i32));
+            dispatch::<F>(conn, pool, event, opaque);
+        }
+
+        unsafe extern "C" fn generic_callback<F>(conn: sys::virConnectPtr,
+                                                 pool: sys::virStoragePoolPtr,
+                                                 opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &StoragePool, StoragePoolEvent)
+        {
+            dispatch::<F>(conn, pool, StoragePoolEvent::Refresh, opaque);
+        }
+
+        unsafe extern "C" fn free_callback<F>(opaque: *mut libc::c_void) {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(opaque as *mut F))));
+        }
+
+        let data = Box::into_raw(Box::new(callback));
+        unsafe {
+            let cb: unsafe extern "C" fn(sys::virConnectPtr, sys::virStoragePoolPtr, *mut libc::c_void) = match event_id {
+                StoragePoolEventId::Lifecycle => {
+                    let cb: unsafe extern "C" fn(sys::virConnectPtr, sys::virStoragePoolPtr, libc::c_int, libc::c_int, *mut libc::c_void) = lifecycle_callback::<F>;
+                    mem::transmute(cb)
+                }
+                StoragePoolEventId::Refresh => generic_callback::<F>,
+            };
+            let ret = sys::virConnectStoragePoolEventRegisterAny(self.as_ptr(),
+                                                                 pool.map_or(ptr::null_mut(), |p| p.as_ptr()),
+                                                                 event_id as libc::c_int,
+                                                                 Some(cb),
+                                                                 data as *mut libc::c_void,
+                                                                 Some(free_callback::<F>));
+            if ret == -1 {
+                drop(Box::from_raw(data));
+                return Err(Error::last_error());
+            }
+            return Ok(EventRegistration::new(self.as_ptr(), ret as i32, sys::virConnectStoragePoolEventDeregisterAny));
+        }
+    }
+
+    /// Register a callback for secret events of type `event_id`,
+    /// optionally restricted to the secret `secret`.
+    ///
+    /// See `storage_pool_event_register_any()`.
+    pub fn secret_event_register_any<F>(&self,
+                                        secret: Option<&Secret>,
+                                        event_id: SecretEventId,
+                                        callback: F)
+                                        -> Result<EventRegistration, Error>
+        where F: FnMut(&Connect, &Secret, SecretEvent) + Send + 'static
+    {
+        unsafe fn dispatch<F>(conn: sys::virConnectPtr, secret: sys::virSecretPtr, event: SecretEvent, opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &Secret, SecretEvent)
+        {
+            let f = opaque as *mut F;
+            // Both handles are borrowed from libvirt for the duration of
+            // the callback.
+            let conn = Borrowed::new(Connect::new(conn));
+            let secret = Borrowed::new(Secret::new(secret));
+            // Unwinding into libvirt's event loop is undefined
+            // behaviour, so a panic in the callback is dropped here.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| (*f)(&conn, &secret, event)));
+        }
+
+        unsafe extern "C" fn lifecycle_callback<F>(conn: sys::virConnectPtr,
+                                                   secret: sys::virSecretPtr,
+                                                   event: libc::c_int,
+                                                   _detail: libc::c_int,
+                                                   opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &Secret, SecretEvent)
+        {
+            let event = SecretEvent::Lifecycle(SecretLifecycleEvent::from_int(event as i32));
+            dispatch::<F>(conn, secret, event, opaque);
+        }
+
+        unsafe extern "C" fn generic_callback<F>(conn: sys::virConnectPtr,
+                                                 secret: sys::virSecretPtr,
+                                                 opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &Secret, SecretEvent)
+        {
+            dispatch::<F>(conn, secret, SecretEvent::ValueChanged, opaque);
+        }
+
+        unsafe extern "C" fn free_callback<F>(opaque: *mut libc::c_void) {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(opaque as *mut F))));
+        }
+
+        let data = Box::into_raw(Box::new(callback));
+        unsafe {
+            let cb: unsafe extern "C" fn(sys::virConnectPtr, sys::virSecretPtr, *mut libc::c_void) = match event_id {
+                SecretEventId::Lifecycle => {
+                    let cb: unsafe extern "C" fn(sys::virConnectPtr, sys::virSecretPtr, libc::c_int, libc::c_int, *mut libc::c_void) = lifecycle_callback::<F>;
+                    mem::transmute(cb)
+                }
+                SecretEventId::ValueChanged => generic_callback::<F>,
+            };
+            let ret = sys::virConnectSecretEventRegisterAny(self.as_ptr(),
+                                                            secret.map_or(ptr::null_mut(), |s| s.as_ptr()),
+                                                            event_id as libc::c_int,
+                                                            Some(cb),
+                                                            data as *mut libc::c_void,
+                                                            Some(free_callback::<F>));
+            if ret == -1 {
+                drop(Box::from_raw(data));
+                return Err(Error::last_error());
+            }
+            return Ok(EventRegistration::new(self.as_ptr(), ret as i32, sys::virConnectSecretEventDeregisterAny));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn credential(cred_type: ConnectCredentialType) -> sys::virConnectCredential {
+        sys::virConnectCredential {
+            type_: cred_type as libc::c_int,
+            prompt: b"Username\0".as_ptr() as *const libc::c_char,
+            challenge: ptr::null(),
+            defresult: ptr::null(),
+            result: ptr::null_mut(),
+            resultlen: 0,
+        }
+    }
+
+    fn call(auth: &mut ConnectAuth, creds: &mut [sys::virConnectCredential]) -> libc::c_int {
+        unsafe {
+            auth_callback(creds.as_mut_ptr(),
+                          creds.len() as libc::c_uint,
+                          auth as *mut ConnectAuth as *mut libc::c_void)
+        }
+    }
+
+    #[test]
+    fn answers_credentials() {
+        let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname], |creds| {
+            assert_eq!(creds[0].prompt, "Username");
+            creds[0].result = Some("admin".to_string());
+        });
+        let mut creds = [credential(ConnectCredentialType::Authname), credential(ConnectCredentialType::Authname)];
+        assert_eq!(call(&mut auth, &mut creds), 0);
+        unsafe {
+            assert_eq!(CStr::from_ptr(creds[0].result).to_str().unwrap(), "admin");
+            libc::free(creds[0].result as *mut libc::c_void);
+        }
+        assert_eq!(creds[0].resultlen, 5);
+        assert!(creds[1].result.is_null());
+    }
+
+    #[test]
+    fn interior_nul_fails() {
+        let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname], |creds| {
+            creds[0].result = Some("ad\0min".to_string());
+        });
+        let mut creds = [credential(ConnectCredentialType::Authname)];
+        assert_eq!(call(&mut auth, &mut creds), -1);
+        assert!(creds[0].result.is_null());
+    }
+
+    #[test]
+    fn panic_fails() {
+        let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname], |_| panic!("no terminal"));
+        let mut creds = [credential(ConnectCredentialType::Authname)];
+        assert_eq!(call(&mut auth, &mut creds), -1);
+        assert!(creds[0].result.is_null());
+    }
 }
@@ -0,0 +1,457 @@
+//! Fluent construction of domain definitions.
+//!
+//! ```no_run
+//! # use new_libvirt::connect::Connect;
+//! # use new_libvirt::domain::builder::DomainBuilder;
+//! # use new_libvirt::storage_vol::StorageVol;
+//! let conn = Connect::open("qemu:///system").unwrap();
+//! let vol = StorageVol::lookup_by_path(&conn, "/var/lib/libvirt/images/vm1.qcow2").unwrap();
+//! let dom = DomainBuilder::new("vm1")
+//!     .memory_mib(2048)
+//!     .vcpus(2)
+//!     .disk_from_volume(&vol)
+//!     .network_interface("default")
+//!     .virtio_console()
+//!     .vnc_graphics()
+//!     .define(&conn)
+//!     .unwrap();
+//! ```
+
+use connect::Connect;
+use domain::Domain;
+use domain::xml::*;
+use error::{Error, ErrorDomain, ErrorNumber};
+use storage_vol::StorageVol;
+use storage_vol::flags::StorageVolType;
+use xmlutil::parse;
+
+/// Hypervisor a `DomainBuilder` generates a definition for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hypervisor {
+    /// QEMU with KVM acceleration.
+    Kvm,
+    /// QEMU with TCG emulation.
+    Qemu,
+    /// Linux containers.
+    Lxc,
+    /// The `test:///` driver.
+    Test,
+}
+
+impl Hypervisor {
+    /// Value of the `type` attribute of `<domain>`.
+    pub fn domain_type(&self) -> &'static str {
+        match *self {
+            Hypervisor::Kvm => "kvm",
+            Hypervisor::Qemu => "qemu",
+            Hypervisor::Lxc => "lxc",
+            Hypervisor::Test => "test",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum DiskSpec {
+    File { path: String, format: Option<String> },
+    Block { path: String, format: Option<String> },
+    Dir { path: String },
+}
+
+/// Builds a `DomainConfig` for common setups.
+///
+/// Setters never fail, so they can be chained. Errors from looking up
+/// volumes, or from asking for something the hypervisor can't do, are
+/// reported by `build()`.
+#[derive(Debug)]
+pub struct DomainBuilder {
+    name: String,
+    hypervisor: Hypervisor,
+    uuid: Option<String>,
+    arch: Option<String>,
+    memory_kib: u64,
+    vcpus: u32,
+    init: String,
+    disks: Vec<DiskSpec>,
+    networks: Vec<String>,
+    virtio_console: bool,
+    vnc_graphics: bool,
+    error: Option<Error>,
+}
+
+fn unsupported(message: String) -> Error {
+    Error::new(ErrorNumber::ConfigUnsupported, ErrorDomain::Domain, message)
+}
+
+/// Name of the disk at `index`, counting from 0, the way libvirt names
+/// them: "vda" to "vdz", then "vdaa", "vdab" and so on.
+fn disk_name(prefix: &str, index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut i = index + 1;
+    while i > 0 {
+        i -= 1;
+        letters.push(b'a' + (i % 26) as u8);
+        i /= 26;
+    }
+    letters.reverse();
+    format!("{}{}", prefix, String::from_utf8(letters).unwrap())
+}
+
+/// Read the image format of a volume, such as "qcow2", from its XML.
+fn volume_format(vol: &StorageVol) -> Result<Option<String>, Error> {
+    let xml = parse(&vol.get_xml_desc()?)?;
+    Ok(xml.get_child("target")
+        .and_then(|t| t.get_child("format"))
+        .and_then(|f| f.attributes.get("type"))
+        .cloned())
+}
+
+impl DomainBuilder {
+    /// Start a KVM domain named `name` with 1 vCPU and 1 GiB of memory.
+    pub fn new(name: &str) -> DomainBuilder {
+        DomainBuilder {
+            name: name.to_string(),
+            hypervisor: Hypervisor::Kvm,
+            uuid: None,
+            arch: None,
+            memory_kib: 1024 * 1024,
+            vcpus: 1,
+            init: "/sbin/init".to_string(),
+            disks: Vec::new(),
+            networks: Vec::new(),
+            virtio_console: false,
+            vnc_graphics: false,
+            error: None,
+        }
+    }
+
+    pub fn hypervisor(mut self, hypervisor: Hypervisor) -> DomainBuilder {
+        self.hypervisor = hypervisor;
+        self
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> DomainBuilder {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    /// Guest CPU architecture, such as "x86_64". The hypervisor picks
+    /// the host architecture when unset.
+    pub fn arch(mut self, arch: &str) -> DomainBuilder {
+        self.arch = Some(arch.to_string());
+        self
+    }
+
+    pub fn memory_kib(mut self, kib: u64) -> DomainBuilder {
+        self.memory_kib = kib;
+        self
+    }
+
+    pub fn memory_mib(self, mib: u64) -> DomainBuilder {
+        self.memory_kib(mib * 1024)
+    }
+
+    pub fn vcpus(mut self, vcpus: u32) -> DomainBuilder {
+        self.vcpus = vcpus;
+        self
+    }
+
+    /// Program started as PID 1 of an LXC container. Defaults to
+    /// "/sbin/init"; ignored by the other hypervisors.
+    pub fn init(mut self, init: &str) -> DomainBuilder {
+        self.init = init.to_string();
+        self
+    }
+
+    /// Attach the image at `path` as the next disk. `format` is the
+    /// image format, such as "raw" or "qcow2".
+    pub fn disk_from_path(mut self, path: &str, format: Option<&str>) -> DomainBuilder {
+        self.disks.push(DiskSpec::File {
+            path: path.to_string(),
+            format: format.map(|f| f.to_string()),
+        });
+        self
+    }
+
+    /// Attach a storage volume as the next disk, using its path and
+    /// format. Containers mount the first volume as their root
+    /// filesystem and the others under /mnt.
+    pub fn disk_from_volume(mut self, vol: &StorageVol) -> DomainBuilder {
+        match DomainBuilder::volume_spec(vol) {
+            Ok(spec) => self.disks.push(spec),
+            Err(e) => {
+                if self.error.is_none() {
+                    self.error = Some(e);
+                }
+            }
+        }
+        self
+    }
+
+    fn volume_spec(vol: &StorageVol) -> Result<DiskSpec, Error> {
+        let path = vol.get_path()?;
+        match vol.get_info()?.type_ {
+            StorageVolType::File => {
+                Ok(DiskSpec::File {
+                    path: path,
+                    format: volume_format(vol)?,
+                })
+            }
+            StorageVolType::Block => {
+                Ok(DiskSpec::Block {
+                    path: path,
+                    format: volume_format(vol)?,
+                })
+            }
+            StorageVolType::Dir => Ok(DiskSpec::Dir { path: path }),
+            t => Err(unsupported(format!("volume '{}' of type {:?} can't be attached by path", path, t))),
+        }
+    }
+
+    /// Connect a network interface to the virtual network `network`.
+    pub fn network_interface(mut self, network: &str) -> DomainBuilder {
+        self.networks.push(network.to_string());
+        self
+    }
+
+    /// Add a text console: virtio for virtual machines and the native
+    /// console for containers.
+    pub fn virtio_console(mut self) -> DomainBuilder {
+        self.virtio_console = true;
+        self
+    }
+
+    /// Add a VNC display on an automatically allocated port. Not
+    /// available for containers.
+    pub fn vnc_graphics(mut self) -> DomainBuilder {
+        self.vnc_graphics = true;
+        self
+    }
+
+    fn is_container(&self) -> bool {
+        self.hypervisor == Hypervisor::Lxc
+    }
+
+    fn vm_disk(&self, index: usize, spec: &DiskSpec) -> Result<Disk, Error> {
+        let (disk_type, format, source) = match *spec {
+            DiskSpec::File { ref path, ref format } => {
+                ("file",
+                 format,
+                 DiskSource { file: Some(path.clone()), ..DiskSource::default() })
+            }
+            DiskSpec::Block { ref path, ref format } => {
+                ("block",
+                 format,
+                 DiskSource { dev: Some(path.clone()), ..DiskSource::default() })
+            }
+            DiskSpec::Dir { ref path } => {
+                return Err(unsupported(format!("directory '{}' can't be used as a virtual machine disk", path)))
+            }
+        };
+        let driver = match self.hypervisor {
+            Hypervisor::Kvm | Hypervisor::Qemu => {
+                Some(DiskDriver {
+                    name: Some("qemu".to_string()),
+                    driver_type: Some(format.clone().unwrap_or_else(|| "raw".to_string())),
+                    ..DiskDriver::default()
+                })
+            }
+            _ => None,
+        };
+        Ok(Disk {
+            disk_type: Some(disk_type.to_string()),
+            device: Some("disk".to_string()),
+            driver: driver,
+            source: Some(source),
+            target: Some(DiskTarget {
+                dev: Some(disk_name("vd", index)),
+                bus: Some("virtio".to_string()),
+                extra: Extra::default(),
+            }),
+            backing_store: None,
+            readonly: false,
+            shareable: false,
+            serial: None,
+            boot_order: None,
+            alias: None,
+            extra: Extra::default(),
+        })
+    }
+
+    fn container_filesystem(&self, index: usize, spec: &DiskSpec) -> Filesystem {
+        let target = if index == 0 {
+            "/".to_string()
+        } else {
+            format!("/mnt/disk{}", index)
+        };
+        let mut fs = Filesystem {
+            fs_type: None,
+            accessmode: None,
+            driver: None,
+            source_dir: None,
+            target_dir: Some(target),
+            readonly: false,
+            alias: None,
+            extra: Extra::default(),
+        };
+        match *spec {
+            DiskSpec::Dir { ref path } => {
+                fs.fs_type = Some("mount".to_string());
+                fs.source_dir = Some(path.clone());
+            }
+            DiskSpec::File { ref path, ref format } => {
+                // Image files are mounted through a loop or nbd device,
+                // which need both a driver type and a format.
+                let mut driver = Element::new("driver");
+                let format = format.clone().unwrap_or_else(|| "raw".to_string());
+                let kind = if format == "raw" { "loop" } else { "nbd" };
+                driver.attributes.insert("type".to_string(), kind.to_string());
+                driver.attributes.insert("format".to_string(), format);
+                let mut source = Element::new("source");
+                source.attributes.insert("file".to_string(), path.clone());
+                fs.fs_type = Some("file".to_string());
+                fs.extra.children.push(driver);
+                fs.extra.children.push(source);
+            }
+            DiskSpec::Block { ref path, .. } => {
+                let mut source = Element::new("source");
+                source.attributes.insert("dev".to_string(), path.clone());
+                fs.fs_type = Some("block".to_string());
+                fs.extra.children.push(source);
+            }
+        }
+        fs
+    }
+
+    /// Generate the domain definition.
+    pub fn build(self) -> Result<DomainConfig, Error> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if self.is_container() && self.vnc_graphics {
+            return Err(unsupported("VNC graphics are not supported for LXC containers".to_string()));
+        }
+
+        let mut config = DomainConfig::new(self.hypervisor.domain_type(), &self.name);
+        config.uuid = self.uuid.clone();
+        config.memory = Some(Memory::new(self.memory_kib, MemoryUnit::KiB));
+        config.vcpu = Some(Vcpu {
+            count: self.vcpus,
+            placement: None,
+            cpuset: None,
+            current: None,
+            extra: Extra::default(),
+        });
+        config.os = Some(Os {
+            os_type: OsType {
+                value: if self.is_container() { "exe" } else { "hvm" }.to_string(),
+                arch: self.arch.clone(),
+                machine: None,
+                extra: Extra::default(),
+            },
+            loader: None,
+            boot: if self.is_container() { vec![] } else { vec!["hd".to_string()] },
+            kernel: None,
+            initrd: None,
+            cmdline: None,
+            init: if self.is_container() { Some(self.init.clone()) } else { None },
+            extra: Extra::default(),
+        });
+        if !self.is_container() {
+            config.features = Some(Features {
+                acpi: true,
+                apic: true,
+                ..Features::default()
+            });
+            config.clock = Some(Clock {
+                offset: Some("utc".to_string()),
+                ..Clock::default()
+            });
+        }
+        config.on_poweroff = Some("destroy".to_string());
+        config.on_reboot = Some("restart".to_string());
+        config.on_crash = Some("destroy".to_string());
+
+        for (i, spec) in self.disks.iter().enumerate() {
+            if self.is_container() {
+                let fs = self.container_filesystem(i, spec);
+                config.devices.filesystems.push(fs);
+            } else {
+                let disk = self.vm_disk(i, spec)?;
+                config.devices.disks.push(disk);
+            }
+        }
+        for network in &self.networks {
+            let model = match self.hypervisor {
+                Hypervisor::Kvm | Hypervisor::Qemu => Some("virtio".to_string()),
+                _ => None,
+            };
+            config.devices.interfaces.push(Interface {
+                interface_type: Some("network".to_string()),
+                mac: None,
+                source: Some(InterfaceSource {
+                    network: Some(network.clone()),
+                    ..InterfaceSource::default()
+                }),
+                model: model,
+                target: None,
+                boot_order: None,
+                alias: None,
+                extra: Extra::default(),
+            });
+        }
+        if self.virtio_console {
+            config.devices.consoles.push(CharDevice {
+                kind: "console".to_string(),
+                device_type: Some("pty".to_string()),
+                source: None,
+                target: Some(CharTarget {
+                    target_type: Some(if self.is_container() { "lxc" } else { "virtio" }.to_string()),
+                    ..CharTarget::default()
+                }),
+                alias: None,
+                extra: Extra::default(),
+            });
+        }
+        if self.vnc_graphics {
+            let mut listen = Element::new("listen");
+            listen.attributes.insert("type".to_string(), "address".to_string());
+            let mut graphics = Graphics {
+                graphics_type: Some("vnc".to_string()),
+                port: Some(-1),
+                autoport: Some(true),
+                listen: None,
+                passwd: None,
+                keymap: None,
+                extra: Extra::default(),
+            };
+            graphics.extra.children.push(listen);
+            config.devices.graphics.push(graphics);
+        }
+        Ok(config)
+    }
+
+    /// Generate the domain XML.
+    pub fn to_xml(self) -> Result<String, Error> {
+        self.build().map(|c| c.to_xml())
+    }
+
+    /// Define the domain on `conn`, without starting it.
+    pub fn define(self, conn: &Connect) -> Result<Domain, Error> {
+        Domain::define(conn, &self.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::disk_name;
+
+    #[test]
+    fn disk_names() {
+        assert_eq!(disk_name("vd", 0), "vda");
+        assert_eq!(disk_name("vd", 25), "vdz");
+        assert_eq!(disk_name("vd", 26), "vdaa");
+        assert_eq!(disk_name("vd", 27), "vdab");
+        assert_eq!(disk_name("vd", 701), "vdzz");
+        assert_eq!(disk_name("vd", 702), "vdaaa");
+    }
+}
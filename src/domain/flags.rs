@@ -69,6 +69,23 @@ bitflags! {
     }
 }
 
+bitflags! {
+    pub struct ShutdownFlags: u32 {
+        /// Hypervisor choice
+        const DEFAULT = 0;
+        /// Send ACPI event
+        const ACPI_POWER_BTN = 1;
+        /// Use guest agent
+        const GUEST_AGENT = 2;
+        /// Use initctl
+        const INITCTL = 4;
+        /// Send a signal
+        const SIGNAL = 8;
+        /// Use paravirt guest control
+        const PARAVIRT = 16;
+    }
+}
+
 bitflags! {
     pub struct DomainMemoryModFlags: u32 {
         const CURRENT = 0;
@@ -107,3 +124,83 @@ bitflags! {
         const PAUSED = 4;
     }
 }
+
+bitflags! {
+    pub struct DomainSaveImageXmlFlags: u32 {
+        /// Dump security sensitive information too
+        const SECURE = 1;
+    }
+}
+
+bitflags! {
+    pub struct DomainCoreDumpFlags: u32 {
+        /// Crash after dump
+        const CRASH = 1;
+        /// Live dump
+        const LIVE = 2;
+        /// Avoid file system cache pollution
+        const BYPASS_CACHE = 4;
+        /// Reset domain after dump finishes
+        const RESET = 8;
+        /// Use dump-guest-memory
+        const MEMORY_ONLY = 16;
+    }
+}
+
+bitflags! {
+    pub struct DomainJobStatsFlags: u32 {
+        /// Return stats of a recently completed job
+        const COMPLETED = 1;
+        /// Don't remove completed job stats after reading them
+        const KEEP_COMPLETED = 2;
+    }
+}
+
+bitflags! {
+    pub struct DomainSetUserPasswordFlags: u32 {
+        /// The password is already encrypted
+        const ENCRYPTED = 1;
+    }
+}
+
+bitflags! {
+    pub struct DomainSetTimeFlags: u32 {
+        /// Re-sync domain time from domain's RTC
+        const SYNC = 1;
+    }
+}
+
+bitflags! {
+    pub struct DomainGuestInfoTypes: u32 {
+        /// Return active users
+        const USERS = 1;
+        /// Return OS information
+        const OS = 2;
+        /// Return timezone information
+        const TIMEZONE = 4;
+        /// Return hostname information
+        const HOSTNAME = 8;
+        /// Return filesystem information
+        const FILESYSTEM = 16;
+        /// Return disks information
+        const DISKS = 32;
+        /// Return interfaces information
+        const INTERFACES = 64;
+    }
+}
+
+bitflags! {
+    pub struct DomainAuthorizedSshKeysFlags: u32 {
+        /// Don't truncate file, just append
+        const APPEND = 1;
+        /// Remove keys, instead of adding them
+        const REMOVE = 2;
+    }
+}
+
+bitflags! {
+    pub struct DomainBlockResizeFlags: u32 {
+        /// Size is in bytes instead of KiB
+        const BYTES = 1;
+    }
+}
@@ -0,0 +1,173 @@
+//! Helpers for typing text into a guest with `Domain::send_key()`.
+
+pub const KEY_ESC: u32 = 1;
+pub const KEY_BACKSPACE: u32 = 14;
+pub const KEY_TAB: u32 = 15;
+pub const KEY_ENTER: u32 = 28;
+pub const KEY_LEFTCTRL: u32 = 29;
+pub const KEY_LEFTSHIFT: u32 = 42;
+pub const KEY_LEFTALT: u32 = 56;
+pub const KEY_SPACE: u32 = 57;
+
+/// Map an ASCII character to its Linux keycode on a US keyboard
+/// layout, and whether shift must be held to produce it.
+pub fn linux_keycode_from_ascii(c: char) -> Option<(u32, bool)> {
+    let code = match c {
+        '1' => (2, false),
+        '!' => (2, true),
+        '2' => (3, false),
+        '@' => (3, true),
+        '3' => (4, false),
+        '#' => (4, true),
+        '4' => (5, false),
+        '$' => (5, true),
+        '5' => (6, false),
+        '%' => (6, true),
+        '6' => (7, false),
+        '^' => (7, true),
+        '7' => (8, false),
+        '&' => (8, true),
+        '8' => (9, false),
+        '*' => (9, true),
+        '9' => (10, false),
+        '(' => (10, true),
+        '0' => (11, false),
+        ')' => (11, true),
+        '-' => (12, false),
+        '_' => (12, true),
+        '=' => (13, false),
+        '+' => (13, true),
+        '[' => (26, false),
+        '{' => (26, true),
+        ']' => (27, false),
+        '}' => (27, true),
+        ';' => (39, false),
+        ':' => (39, true),
+        '\'' => (40, false),
+        '"' => (40, true),
+        '`' => (41, false),
+        '~' => (41, true),
+        '\\' => (43, false),
+        '|' => (43, true),
+        ',' => (51, false),
+        '<' => (51, true),
+        '.' => (52, false),
+        '>' => (52, true),
+        '/' => (53, false),
+        '?' => (53, true),
+        'q' => (16, false),
+        'Q' => (16, true),
+        'w' => (17, false),
+        'W' => (17, true),
+        'e' => (18, false),
+        'E' => (18, true),
+        'r' => (19, false),
+        'R' => (19, true),
+        't' => (20, false),
+        'T' => (20, true),
+        'y' => (21, false),
+        'Y' => (21, true),
+        'u' => (22, false),
+        'U' => (22, true),
+        'i' => (23, false),
+        'I' => (23, true),
+        'o' => (24, false),
+        'O' => (24, true),
+        'p' => (25, false),
+        'P' => (25, true),
+        'a' => (30, false),
+        'A' => (30, true),
+        's' => (31, false),
+        'S' => (31, true),
+        'd' => (32, false),
+        'D' => (32, true),
+        'f' => (33, false),
+        'F' => (33, true),
+        'g' => (34, false),
+        'G' => (34, true),
+        'h' => (35, false),
+        'H' => (35, true),
+        'j' => (36, false),
+        'J' => (36, true),
+        'k' => (37, false),
+        'K' => (37, true),
+        'l' => (38, false),
+        'L' => (38, true),
+        'z' => (44, false),
+        'Z' => (44, true),
+        'x' => (45, false),
+        'X' => (45, true),
+        'c' => (46, false),
+        'C' => (46, true),
+        'v' => (47, false),
+        'V' => (47, true),
+        'b' => (48, false),
+        'B' => (48, true),
+        'n' => (49, false),
+        'N' => (49, true),
+        'm' => (50, false),
+        'M' => (50, true),
+        ' ' => (KEY_SPACE, false),
+        '\t' => (KEY_TAB, false),
+        '\n' => (KEY_ENTER, false),
+        '\x08' => (KEY_BACKSPACE, false),
+        '\x1b' => (KEY_ESC, false),
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Convert an ASCII string into the key presses needed to type it,
+/// using Linux keycodes on a US keyboard layout. Each element is the
+/// set of keys to press together in one `Domain::send_key()` call.
+///
+/// Returns `None` if `text` contains a character that cannot be typed.
+pub fn linux_keycodes_from_ascii(text: &str) -> Option<Vec<Vec<u32>>> {
+    text.chars()
+        .map(|c| {
+            linux_keycode_from_ascii(c).map(|(code, shift)| {
+                if shift {
+                    vec![KEY_LEFTSHIFT, code]
+                } else {
+                    vec![code]
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unshifted_characters() {
+        assert_eq!(linux_keycodes_from_ascii("a1-"), Some(vec![vec![30], vec![2], vec![12]]));
+    }
+
+    #[test]
+    fn shifted_characters() {
+        assert_eq!(linux_keycodes_from_ascii("A!_"),
+                   Some(vec![vec![KEY_LEFTSHIFT, 30], vec![KEY_LEFTSHIFT, 2], vec![KEY_LEFTSHIFT, 12]]));
+        assert_eq!(linux_keycode_from_ascii('?'), Some((53, true)));
+        assert_eq!(linux_keycode_from_ascii('/'), Some((53, false)));
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(linux_keycodes_from_ascii(" \t\n"),
+                   Some(vec![vec![KEY_SPACE], vec![KEY_TAB], vec![KEY_ENTER]]));
+    }
+
+    #[test]
+    fn unsupported_characters() {
+        assert_eq!(linux_keycode_from_ascii('\r'), None);
+        assert_eq!(linux_keycodes_from_ascii("pass\u{e9}"), None);
+        assert_eq!(linux_keycodes_from_ascii("\x07"), None);
+    }
+
+    #[test]
+    fn empty_text() {
+        assert_eq!(linux_keycodes_from_ascii(""), Some(vec![]));
+    }
+}
@@ -1,14 +1,23 @@
 extern crate libc;
 extern crate libvirt_sys as sys;
-use error::Error;
+use error::{Error, ErrorDomain, ErrorNumber};
 
 use connect::Connect;
-use std::ffi::CStr;
+use handle::Borrowed;
+use stream::Stream;
+use stream::flags::StreamFlags;
+use typed_params::TypedParams;
+use std::ffi::{CStr, CString};
 use std::{ptr, slice, mem};
 use std::os::raw::c_int;
 
+pub mod builder;
 pub mod flags;
+pub mod keycodes;
+pub mod state;
+pub mod xml;
 use domain::flags::*;
+use domain::state::DomainStateReason;
 
 #[derive(Clone, Debug)]
 pub struct InterfaceStats {
@@ -109,6 +118,574 @@ impl DomainState {
     }
 }
 
+/// Format of a guest core dump, see `Domain::core_dump_with_format()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainCoreDumpFormat {
+    /// Dump guest memory in raw format
+    Raw = 0,
+    /// kdump-compressed format, with zlib compression
+    KdumpZlib = 1,
+    /// kdump-compressed format, with lzo compression
+    KdumpLzo = 2,
+    /// kdump-compressed format, with snappy compression
+    KdumpSnappy = 3,
+    /// Windows full crashdump format
+    WinDmp = 4,
+}
+
+/// How `Domain::memory_peek()` interprets its start address.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainMemoryPeekAddress {
+    /// Addresses are virtual addresses
+    Virtual = 1,
+    /// Addresses are physical addresses
+    Physical = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainJobType {
+    /// No job is active
+    None = 0,
+    /// Job with a finite completion time
+    Bounded = 1,
+    /// Job without a finite completion time
+    Unbounded = 2,
+    /// Job has finished, but isn't cleaned up
+    Completed = 3,
+    /// Job hit error, but isn't cleaned up
+    Failed = 4,
+    /// Job was aborted, but isn't cleaned up
+    Cancelled = 5,
+}
+
+impl DomainJobType {
+    pub fn from_int(n: i32) -> Option<Self> {
+        match n {
+            0 => Some(DomainJobType::None),
+            1 => Some(DomainJobType::Bounded),
+            2 => Some(DomainJobType::Unbounded),
+            3 => Some(DomainJobType::Completed),
+            4 => Some(DomainJobType::Failed),
+            5 => Some(DomainJobType::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Progress of a background job, such as a save, dump or migration.
+#[derive(Clone, Debug)]
+pub struct DomainJobInfo {
+    pub type_: DomainJobType,
+    /// Time elapsed in milliseconds.
+    pub time_elapsed: u64,
+    /// Time remaining in milliseconds, only for bounded jobs.
+    pub time_remaining: u64,
+    /// Total data in bytes.
+    pub data_total: u64,
+    /// Data processed in bytes.
+    pub data_processed: u64,
+    /// Data remaining in bytes.
+    pub data_remaining: u64,
+    /// Total memory in bytes.
+    pub mem_total: u64,
+    /// Memory processed in bytes.
+    pub mem_processed: u64,
+    /// Memory remaining in bytes.
+    pub mem_remaining: u64,
+    /// Total file in bytes.
+    pub file_total: u64,
+    /// File processed in bytes.
+    pub file_processed: u64,
+    /// File remaining in bytes.
+    pub file_remaining: u64,
+}
+
+impl DomainJobInfo {
+    pub fn from_ptr(ptr: sys::virDomainJobInfoPtr) -> DomainJobInfo {
+        unsafe {
+            DomainJobInfo {
+                type_: DomainJobType::from_int((*ptr).type_).unwrap_or(DomainJobType::None),
+                time_elapsed: (*ptr).timeElapsed as u64,
+                time_remaining: (*ptr).timeRemaining as u64,
+                data_total: (*ptr).dataTotal as u64,
+                data_processed: (*ptr).dataProcessed as u64,
+                data_remaining: (*ptr).dataRemaining as u64,
+                mem_total: (*ptr).memTotal as u64,
+                mem_processed: (*ptr).memProcessed as u64,
+                mem_remaining: (*ptr).memRemaining as u64,
+                file_total: (*ptr).fileTotal as u64,
+                file_processed: (*ptr).fileProcessed as u64,
+                file_remaining: (*ptr).fileRemaining as u64,
+            }
+        }
+    }
+}
+
+/// Detailed statistics about a background job, as returned by
+/// `Domain::get_job_stats()`.
+///
+/// Hypervisors only report the statistics relevant to the kind of job
+/// that is running, so every field is optional.
+#[derive(Clone, Debug)]
+pub struct DomainJobStats {
+    pub type_: DomainJobType,
+    /// The operation which started the job, one of
+    /// virDomainJobOperation.
+    pub operation: Option<i32>,
+    /// Time elapsed in milliseconds since the beginning of the job.
+    pub time_elapsed: Option<u64>,
+    /// Time elapsed in milliseconds since the job was started on the
+    /// destination host, for migrations.
+    pub time_elapsed_net: Option<u64>,
+    /// Time remaining in milliseconds, only for bounded jobs.
+    pub time_remaining: Option<u64>,
+    /// Expected or actual downtime in milliseconds.
+    pub downtime: Option<u64>,
+    /// Time in milliseconds spent preparing the job.
+    pub setup_time: Option<u64>,
+    /// Total bytes to be transferred.
+    pub data_total: Option<u64>,
+    /// Bytes transferred so far.
+    pub data_processed: Option<u64>,
+    /// Bytes still to be transferred.
+    pub data_remaining: Option<u64>,
+    /// Guest memory in bytes to be transferred.
+    pub memory_total: Option<u64>,
+    /// Guest memory in bytes transferred so far.
+    pub memory_processed: Option<u64>,
+    /// Guest memory in bytes still to be transferred.
+    pub memory_remaining: Option<u64>,
+    /// Number of pages filled with a constant byte.
+    pub memory_constant: Option<u64>,
+    /// Number of pages transferred without any compression.
+    pub memory_normal: Option<u64>,
+    /// Bytes transferred as normal pages.
+    pub memory_normal_bytes: Option<u64>,
+    /// Memory transfer bandwidth in bytes per second.
+    pub memory_bps: Option<u64>,
+    /// Number of memory pages dirtied by the guest per second.
+    pub memory_dirty_rate: Option<u64>,
+    /// Size of a memory page in bytes.
+    pub memory_page_size: Option<u64>,
+    /// Number of iterations the memory transfer went through.
+    pub memory_iteration: Option<u64>,
+    /// Number of page requests received from the destination during
+    /// post-copy migration.
+    pub memory_postcopy_requests: Option<u64>,
+    /// Total disk data in bytes to be transferred.
+    pub disk_total: Option<u64>,
+    /// Disk data in bytes transferred so far.
+    pub disk_processed: Option<u64>,
+    /// Disk data in bytes still to be transferred.
+    pub disk_remaining: Option<u64>,
+    /// Disk transfer bandwidth in bytes per second.
+    pub disk_bps: Option<u64>,
+    /// Size of the compression cache in bytes.
+    pub compression_cache: Option<u64>,
+    /// Number of compressed bytes transferred.
+    pub compression_bytes: Option<u64>,
+    /// Number of compressed pages transferred.
+    pub compression_pages: Option<u64>,
+    /// Number of pages not found in the compression cache.
+    pub compression_cache_misses: Option<u64>,
+    /// Number of pages sent uncompressed because compression did not
+    /// reduce their size.
+    pub compression_overflow: Option<u64>,
+    /// Percentage of CPU time the guest is throttled by auto-converge.
+    pub auto_converge_throttle: Option<i32>,
+    /// Whether a completed job succeeded.
+    pub success: Option<bool>,
+    /// Error message of a failed job.
+    pub error_message: Option<String>,
+}
+
+impl DomainJobStats {
+    fn from_params(type_: DomainJobType, params: &TypedParams) -> DomainJobStats {
+        DomainJobStats {
+            type_: type_,
+            operation: params.get_int("operation"),
+            time_elapsed: params.get_ullong("time_elapsed"),
+            time_elapsed_net: params.get_ullong("time_elapsed_net"),
+            time_remaining: params.get_ullong("time_remaining"),
+            downtime: params.get_ullong("downtime"),
+            setup_time: params.get_ullong("setup_time"),
+            data_total: params.get_ullong("data_total"),
+            data_processed: params.get_ullong("data_processed"),
+            data_remaining: params.get_ullong("data_remaining"),
+            memory_total: params.get_ullong("memory_total"),
+            memory_processed: params.get_ullong("memory_processed"),
+            memory_remaining: params.get_ullong("memory_remaining"),
+            memory_constant: params.get_ullong("memory_constant"),
+            memory_normal: params.get_ullong("memory_normal"),
+            memory_normal_bytes: params.get_ullong("memory_normal_bytes"),
+            memory_bps: params.get_ullong("memory_bps"),
+            memory_dirty_rate: params.get_ullong("memory_dirty_rate"),
+            memory_page_size: params.get_ullong("memory_page_size"),
+            memory_iteration: params.get_ullong("memory_iteration"),
+            memory_postcopy_requests: params.get_ullong("memory_postcopy_requests"),
+            disk_total: params.get_ullong("disk_total"),
+            disk_processed: params.get_ullong("disk_processed"),
+            disk_remaining: params.get_ullong("disk_remaining"),
+            disk_bps: params.get_ullong("disk_bps"),
+            compression_cache: params.get_ullong("compression_cache"),
+            compression_bytes: params.get_ullong("compression_bytes"),
+            compression_pages: params.get_ullong("compression_pages"),
+            compression_cache_misses: params.get_ullong("compression_cache_misses"),
+            compression_overflow: params.get_ullong("compression_overflow"),
+            auto_converge_throttle: params.get_int("auto_converge_throttle"),
+            success: params.get_bool("success"),
+            error_message: params.get_string("errmsg"),
+        }
+    }
+}
+
+/// A filesystem mounted in the guest, as reported by the guest agent
+/// through `Domain::get_fs_info()`.
+#[derive(Clone, Debug)]
+pub struct DomainFsInfo {
+    /// Path to mount point.
+    pub mountpoint: String,
+    /// Device name in the guest (e.g. "sda1").
+    pub name: String,
+    /// Filesystem type.
+    pub fstype: String,
+    /// Aliases of the disk devices backing the filesystem.
+    pub dev_aliases: Vec<String>,
+}
+
+impl DomainFsInfo {
+    pub fn from_ptr(ptr: sys::virDomainFSInfoPtr) -> DomainFsInfo {
+        unsafe {
+            let aliases = slice::from_raw_parts((*ptr).devAlias, (*ptr).ndevAlias as usize);
+            DomainFsInfo {
+                mountpoint: c_chars_to_string!((*ptr).mountpoint, nofree),
+                name: c_chars_to_string!((*ptr).name, nofree),
+                fstype: c_chars_to_string!((*ptr).fstype, nofree),
+                dev_aliases: aliases.iter().map(|a| c_chars_to_string!(*a, nofree)).collect(),
+            }
+        }
+    }
+}
+
+/// Online state of the guest's virtual CPUs, as reported by the guest
+/// agent. Each field is a cpumap string such as "0-3,5".
+#[derive(Clone, Debug)]
+pub struct GuestVcpus {
+    /// All vCPUs the guest knows about.
+    pub vcpus: String,
+    /// vCPUs which are online.
+    pub online: String,
+    /// vCPUs which can be taken offline.
+    pub offlinable: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuestUser {
+    pub name: String,
+    /// Domain of the user, on Windows guests.
+    pub domain: Option<String>,
+    /// Login time in milliseconds since the epoch.
+    pub login_time: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GuestOsInfo {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub pretty_name: Option<String>,
+    pub version: Option<String>,
+    pub version_id: Option<String>,
+    pub kernel_release: Option<String>,
+    pub kernel_version: Option<String>,
+    pub machine: Option<String>,
+    pub variant: Option<String>,
+    pub variant_id: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuestTimezone {
+    pub name: Option<String>,
+    /// Offset to UTC in seconds.
+    pub offset: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuestFsDisk {
+    /// Alias of the disk in the domain XML.
+    pub alias: Option<String>,
+    pub serial: Option<String>,
+    /// Device node in the guest.
+    pub device: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuestFilesystem {
+    pub mountpoint: String,
+    pub name: String,
+    pub fstype: Option<String>,
+    pub total_bytes: Option<u64>,
+    pub used_bytes: Option<u64>,
+    pub disks: Vec<GuestFsDisk>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuestDisk {
+    /// Device node in the guest.
+    pub name: String,
+    /// Whether the disk is a partition.
+    pub partition: bool,
+    /// Device nodes this disk depends on, such as the parent of a
+    /// partition.
+    pub dependencies: Vec<String>,
+    pub serial: Option<String>,
+    /// Alias of the disk in the domain XML.
+    pub alias: Option<String>,
+    /// Optional alias assigned by the guest.
+    pub guest_alias: Option<String>,
+}
+
+/// Information collected from the guest agent by
+/// `Domain::get_guest_info()`. Only the categories requested through
+/// `DomainGuestInfoTypes` are filled in.
+#[derive(Clone, Debug, Default)]
+pub struct GuestInfo {
+    pub users: Option<Vec<GuestUser>>,
+    pub os: Option<GuestOsInfo>,
+    pub timezone: Option<GuestTimezone>,
+    pub hostname: Option<String>,
+    pub filesystems: Option<Vec<GuestFilesystem>>,
+    pub disks: Option<Vec<GuestDisk>>,
+}
+
+impl GuestInfo {
+    fn from_params(params: &TypedParams) -> GuestInfo {
+        let mut info = GuestInfo::default();
+
+        if let Some(count) = params.get_uint("user.count") {
+            info.users = Some((0..count).map(|i| GuestUser {
+                name: params.get_string(&format!("user.{}.name", i)).unwrap_or_default(),
+                domain: params.get_string(&format!("user.{}.domain", i)),
+                login_time: params.get_ullong(&format!("user.{}.login-time", i)),
+            }).collect());
+        }
+
+        let os = GuestOsInfo {
+            id: params.get_string("os.id"),
+            name: params.get_string("os.name"),
+            pretty_name: params.get_string("os.pretty-name"),
+            version: params.get_string("os.version"),
+            version_id: params.get_string("os.version-id"),
+            kernel_release: params.get_string("os.kernel-release"),
+            kernel_version: params.get_string("os.kernel-version"),
+            machine: params.get_string("os.machine"),
+            variant: params.get_string("os.variant"),
+            variant_id: params.get_string("os.variant-id"),
+        };
+        if os.id.is_some() || os.name.is_some() || os.kernel_release.is_some() || os.machine.is_some() {
+            info.os = Some(os);
+        }
+
+        if let Some(offset) = params.get_int("timezone.offset") {
+            info.timezone = Some(GuestTimezone {
+                name: params.get_string("timezone.name"),
+                offset: offset,
+            });
+        }
+
+        info.hostname = params.get_string("hostname");
+
+        if let Some(count) = params.get_uint("fs.count") {
+            info.filesystems = Some((0..count).map(|i| {
+                let ndisks = params.get_uint(&format!("fs.{}.disk.count", i)).unwrap_or(0);
+                GuestFilesystem {
+                    mountpoint: params.get_string(&format!("fs.{}.mountpoint", i)).unwrap_or_default(),
+                    name: params.get_string(&format!("fs.{}.name", i)).unwrap_or_default(),
+                    fstype: params.get_string(&format!("fs.{}.fstype", i)),
+                    total_bytes: params.get_ullong(&format!("fs.{}.total-bytes", i)),
+                    used_bytes: params.get_ullong(&format!("fs.{}.used-bytes", i)),
+                    disks: (0..ndisks).map(|j| GuestFsDisk {
+                        alias: params.get_string(&format!("fs.{}.disk.{}.alias", i, j)),
+                        serial: params.get_string(&format!("fs.{}.disk.{}.serial", i, j)),
+                        device: params.get_string(&format!("fs.{}.disk.{}.device", i, j)),
+                    }).collect(),
+                }
+            }).collect());
+        }
+
+        if let Some(count) = params.get_uint("disk.count") {
+            info.disks = Some((0..count).map(|i| {
+                let ndeps = params.get_uint(&format!("disk.{}.dependency.count", i)).unwrap_or(0);
+                GuestDisk {
+                    name: params.get_string(&format!("disk.{}.name", i)).unwrap_or_default(),
+                    partition: params.get_bool(&format!("disk.{}.partition", i)).unwrap_or(false),
+                    dependencies: (0..ndeps)
+                        .filter_map(|j| params.get_string(&format!("disk.{}.dependency.{}.name", i, j)))
+                        .collect(),
+                    serial: params.get_string(&format!("disk.{}.serial", i)),
+                    alias: params.get_string(&format!("disk.{}.alias", i)),
+                    guest_alias: params.get_string(&format!("disk.{}.guest_alias", i)),
+                }
+            }).collect());
+        }
+
+        info
+    }
+}
+
+/// The security label applied to a domain, see
+/// `Domain::get_security_label()`.
+#[derive(Clone, Debug)]
+pub struct SecurityLabel {
+    /// Security label string.
+    pub label: String,
+    /// Whether the security policy is being enforced.
+    pub enforcing: bool,
+}
+
+impl SecurityLabel {
+    pub fn from_ptr(ptr: sys::virSecurityLabelPtr) -> SecurityLabel {
+        unsafe {
+            SecurityLabel {
+                label: c_chars_to_string!((*ptr).label.as_ptr(), nofree),
+                enforcing: (*ptr).enforcing == 1,
+            }
+        }
+    }
+}
+
+/// Size information about a disk, see `Domain::get_block_info()`.
+#[derive(Clone, Debug)]
+pub struct DomainBlockInfo {
+    /// Logical size in bytes of the image, as seen by the guest.
+    pub capacity: u64,
+    /// Highest allocated extent in bytes of the image.
+    pub allocation: u64,
+    /// Physical size in bytes of the container of the image.
+    pub physical: u64,
+}
+
+impl DomainBlockInfo {
+    pub fn from_ptr(ptr: sys::virDomainBlockInfoPtr) -> DomainBlockInfo {
+        unsafe {
+            DomainBlockInfo {
+                capacity: (*ptr).capacity as u64,
+                allocation: (*ptr).allocation as u64,
+                physical: (*ptr).physical as u64,
+            }
+        }
+    }
+}
+
+/// Keycode set of the keycodes passed to `Domain::send_key()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeycodeSet {
+    Linux = 0,
+    Xt = 1,
+    Atset1 = 2,
+    Atset2 = 3,
+    Atset3 = 4,
+    Osx = 5,
+    XtKbd = 6,
+    Usb = 7,
+    Win32 = 8,
+    Qnum = 9,
+}
+
+/// Signals which can be sent to guest processes with
+/// `Domain::send_process_signal()`. The values are independent of the
+/// host and guest operating systems.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainProcessSignal {
+    Nop = 0,
+    Hup = 1,
+    Int = 2,
+    Quit = 3,
+    Ill = 4,
+    Trap = 5,
+    Abrt = 6,
+    Bus = 7,
+    Fpe = 8,
+    Kill = 9,
+    Usr1 = 10,
+    Segv = 11,
+    Usr2 = 12,
+    Pipe = 13,
+    Alrm = 14,
+    Term = 15,
+    Stkflt = 16,
+    Chld = 17,
+    Cont = 18,
+    Stop = 19,
+    Tstp = 20,
+    Ttin = 21,
+    Ttou = 22,
+    Urg = 23,
+    Xcpu = 24,
+    Xfsz = 25,
+    Vtalrm = 26,
+    Prof = 27,
+    Winch = 28,
+    Poll = 29,
+    Pwr = 30,
+    Sys = 31,
+    Rt0 = 32,
+    Rt1 = 33,
+    Rt2 = 34,
+    Rt3 = 35,
+    Rt4 = 36,
+    Rt5 = 37,
+    Rt6 = 38,
+    Rt7 = 39,
+    Rt8 = 40,
+    Rt9 = 41,
+    Rt10 = 42,
+    Rt11 = 43,
+    Rt12 = 44,
+    Rt13 = 45,
+    Rt14 = 46,
+    Rt15 = 47,
+    Rt16 = 48,
+    Rt17 = 49,
+    Rt18 = 50,
+    Rt19 = 51,
+    Rt20 = 52,
+    Rt21 = 53,
+    Rt22 = 54,
+    Rt23 = 55,
+    Rt24 = 56,
+    Rt25 = 57,
+    Rt26 = 58,
+    Rt27 = 59,
+    Rt28 = 60,
+    Rt29 = 61,
+    Rt30 = 62,
+    Rt31 = 63,
+    Rt32 = 64,
+}
+
+/// Power state to enter with `Domain::pm_suspend_for_duration()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PmSuspendTarget {
+    /// Suspend-to-RAM
+    Mem = 0,
+    /// Suspend-to-disk
+    Disk = 1,
+    /// Hybrid-suspend
+    Hybrid = 2,
+}
+
+/// Kind of metadata accessed by `Domain::get_metadata()` and
+/// `Domain::set_metadata()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainMetadataType {
+    /// Operate on <description>
+    Description = 0,
+    /// Operate on <title>
+    Title = 1,
+    /// Operate on <metadata>
+    Element = 2,
+}
+
 #[derive(Debug)]
 pub struct DomainIpAddress {
     // pub type_: IpAddrType,
@@ -127,6 +704,33 @@ pub struct Domain {
     ptr: Option<sys::virDomainPtr>,
 }
 
+impl Drop for Domain {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.free() {
+                panic!("Unable to drop memory for Domain, code {}, message: {}", e.code, e.message)
+            }
+        }
+    }
+}
+
+impl Clone for Domain {
+    /// Take a new reference to the same object.
+    fn clone(&self) -> Domain {
+        unsafe {
+            if sys::virDomainRef(self.as_ptr()) == -1 {
+                let e = Error::last_error();
+                panic!("Unable to add reference to Domain, code {}, message: {}", e.code, e.message)
+            }
+        }
+        Domain::new(self.as_ptr())
+    }
+}
+
+// Like connections, domain objects are locked by libvirt.
+unsafe impl Send for Domain {}
+unsafe impl Sync for Domain {}
+
 impl Domain {
     pub fn new(ptr: sys::virDomainPtr) -> Self {
         return Domain { ptr: Some(ptr) };
@@ -137,13 +741,16 @@ impl Domain {
     }
 
 
-    pub fn get_connect(&self) -> Result<Connect, Error> {
+    /// The connection the object belongs to. libvirt doesn't take a
+    /// reference for the caller, so the connection is borrowed from
+    /// `self`; clone it to keep it longer.
+    pub fn get_connect<'a>(&'a self) -> Result<Borrowed<'a, Connect>, Error> {
         unsafe {
             let ptr = sys::virDomainGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::last_error());
             }
-            return Ok(Connect::new(ptr));
+            return Ok(Borrowed::new(Connect::new(ptr)));
         }
     }
 
@@ -198,7 +805,9 @@ impl Domain {
         }
     }
 
-    pub fn get_state(&self) -> Result<(DomainState, i32), Error> {
+    /// Extract the current state of the domain, together with the
+    /// reason it entered that state.
+    pub fn get_state(&self) -> Result<(DomainState, DomainStateReason), Error> {
         unsafe {
             let mut state: libc::c_int = -1;
             let mut reason: libc::c_int = -1;
@@ -206,7 +815,9 @@ impl Domain {
             if ret == -1 {
                 return Err(Error::last_error());
             }
-            return Ok((DomainState::new(state as u8).unwrap(), reason as i32));
+            let state = DomainState::new(state as u8).unwrap();
+            let reason = DomainStateReason::from_int(&state, reason as i32);
+            return Ok((state, reason));
         }
     }
 
@@ -245,6 +856,17 @@ impl Domain {
         }
     }
 
+    /// Extract the security label of an active domain.
+    pub fn get_security_label(&self) -> Result<SecurityLabel, Error> {
+        unsafe {
+            let mut seclabel: sys::virSecurityLabel = mem::zeroed();
+            if sys::virDomainGetSecurityLabel(self.as_ptr(), &mut seclabel) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(SecurityLabel::from_ptr(&mut seclabel));
+        }
+    }
+
     /// Get the hypervisor ID number for the domain
     pub fn get_id(&self) -> Option<u32> {
         unsafe {
@@ -406,6 +1028,21 @@ impl Domain {
         }
     }
 
+    /// Shutdown a domain, using the methods selected by `flags`.
+    /// Passing `None` will have the same effect as passing
+    /// `Some(ShutdownFlags::DEFAULT)`, and the hypervisor picks the
+    /// method.
+    ///
+    /// See `shutdown()` for details.
+    pub fn shutdown_flags(&self, flags: Option<ShutdownFlags>) -> Result<(), Error> {
+        let flags = flags.and_then(|f| Some(f.bits())).unwrap_or(0);
+        unsafe {
+            if sys::virDomainShutdownFlags(self.as_ptr(), flags) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
 
     /// Reboot a domain. Passing `None` will have the same effect as
     /// passing `Some(RebootFlags::DEFAULT)`
@@ -421,6 +1058,19 @@ impl Domain {
         }
     }
 
+    /// Reset a domain immediately without any guest OS shutdown.
+    /// This is the equivalent of pressing the reset button.
+    ///
+    /// The domain object is still usable thereafter.
+    pub fn reset(&self) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainReset(self.as_ptr(), 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
     /// Suspend a domain.
     ///
     /// Suspends an active domain, the process is frozen without
@@ -456,6 +1106,36 @@ impl Domain {
         }
     }
 
+    /// Put the guest into a system power management state, using the
+    /// guest agent. `duration` is the time in seconds before the
+    /// guest wakes up again; `0` means it stays suspended until
+    /// `pm_wakeup()` is called.
+    ///
+    /// The domain then moves to `DomainState::PmSuspended`.
+    pub fn pm_suspend_for_duration(&self, target: PmSuspendTarget, duration: u64) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainPMSuspendForDuration(self.as_ptr(),
+                                                         target as libc::c_uint,
+                                                         duration as libc::c_ulonglong,
+                                                         0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Inject a wakeup into a guest previously suspended with
+    /// `pm_suspend_for_duration()`.
+    pub fn pm_wakeup(&self) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainPMWakeup(self.as_ptr(), 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
     /// Determine if the domain is currently running.
     pub fn is_active(&self) -> Result<bool, Error> {
         unsafe {
@@ -642,6 +1322,531 @@ impl Domain {
         }
     }
 
+    /// Take a screenshot of the current domain console as a stream.
+    ///
+    /// `screen` selects the head of a multi-head graphics card,
+    /// starting at `0`. The image data is written to `stream`; the
+    /// returned string is the MIME type of the image format chosen by
+    /// the hypervisor.
+    pub fn screenshot(&self, stream: &Stream, screen: u32) -> Result<String, Error> {
+        unsafe {
+            let n = sys::virDomainScreenshot(self.as_ptr(),
+                                             stream.as_ptr(),
+                                             screen as libc::c_uint,
+                                             0);
+            if n.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(n));
+        }
+    }
+
+    /// Take a screenshot of the current domain console and collect
+    /// it into memory. Returns the MIME type together with the image
+    /// data.
+    pub fn screenshot_to_vec(&self, screen: u32) -> Result<(String, Vec<u8>), Error> {
+        let conn = self.get_connect()?;
+        let stream = Stream::create(&conn, StreamFlags::empty())?;
+        let mime = self.screenshot(&stream, screen)?;
+        let data = match stream.recv_all() {
+            Ok(d) => d,
+            Err(e) => {
+                let _ = stream.abort();
+                return Err(e);
+            }
+        };
+        stream.finish()?;
+        return Ok((mime, data));
+    }
+
+    /// Suspend the domain and save its memory contents to the file
+    /// `to` on disk. After the call, the domain is no longer running
+    /// and can be brought back with `domain_restore()`.
+    pub fn save(&self, to: &str) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainSave(self.as_ptr(), string_to_c_chars!(to)) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Like `save()`, but allows supplying a replacement XML
+    /// description with host-specific changes (such as disk paths)
+    /// to be stored in the image.
+    pub fn save_flags(&self, to: &str, dxml: Option<&str>, flags: DomainSaveRestoreFlags) -> Result<(), Error> {
+        let dxml = dxml.map(|c| CString::new(c).unwrap());
+        unsafe {
+            let xml = match dxml {
+                Some(ref c) => c.as_ptr(),
+                None => ptr::null(),
+            };
+
+            if sys::virDomainSaveFlags(self.as_ptr(), string_to_c_chars!(to), xml, flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Suspend the domain and save its memory contents to a file
+    /// managed by libvirt. The next `create()` restores from this
+    /// image instead of booting from scratch.
+    pub fn managed_save(&self, flags: DomainSaveRestoreFlags) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainManagedSave(self.as_ptr(), flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Check if the domain has a managed save image.
+    pub fn has_managed_save_image(&self) -> Result<bool, Error> {
+        unsafe {
+            let ret = sys::virDomainHasManagedSaveImage(self.as_ptr(), 0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(ret == 1);
+        }
+    }
+
+    /// Remove the managed save image of the domain, if any.
+    pub fn managed_save_remove(&self) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainManagedSaveRemove(self.as_ptr(), 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Provide an XML description of the domain as stored in its
+    /// managed save image.
+    pub fn managed_save_get_xml_desc(&self, flags: DomainSaveImageXmlFlags) -> Result<String, Error> {
+        unsafe {
+            let xml = sys::virDomainManagedSaveGetXMLDesc(self.as_ptr(), flags.bits());
+            if xml.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(xml));
+        }
+    }
+
+    /// Replace the XML description stored in the managed save image
+    /// of the domain. `dxml` must only differ from the original in
+    /// host-specific portions.
+    pub fn managed_save_define_xml(&self, dxml: &str, flags: DomainSaveRestoreFlags) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainManagedSaveDefineXML(self.as_ptr(), string_to_c_chars!(dxml), flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Dump the core of the domain to the file `to` for analysis.
+    ///
+    /// The dump runs as a domain job; its progress can be polled from
+    /// another thread with `get_job_info()`.
+    pub fn core_dump(&self, to: &str, flags: DomainCoreDumpFlags) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainCoreDump(self.as_ptr(), string_to_c_chars!(to), flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Dump the core of the domain to the file `to` in the given
+    /// format. Formats other than `DomainCoreDumpFormat::Raw` require
+    /// `DomainCoreDumpFlags::MEMORY_ONLY`.
+    pub fn core_dump_with_format(&self,
+                                 to: &str,
+                                 format: DomainCoreDumpFormat,
+                                 flags: DomainCoreDumpFlags)
+                                 -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainCoreDumpWithFormat(self.as_ptr(),
+                                                       string_to_c_chars!(to),
+                                                       format as libc::c_uint,
+                                                       flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Extract information about progress of a background job on the
+    /// domain.
+    pub fn get_job_info(&self) -> Result<DomainJobInfo, Error> {
+        unsafe {
+            let pinfo = &mut sys::virDomainJobInfo::default();
+            if sys::virDomainGetJobInfo(self.as_ptr(), pinfo) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(DomainJobInfo::from_ptr(pinfo));
+        }
+    }
+
+    /// Extract detailed statistics about a background job on the
+    /// domain. With `DomainJobStatsFlags::COMPLETED`, the statistics
+    /// of the most recently completed job are returned instead.
+    pub fn get_job_stats(&self, flags: DomainJobStatsFlags) -> Result<DomainJobStats, Error> {
+        unsafe {
+            let mut type_: libc::c_int = 0;
+            let mut params = TypedParams::new();
+            let ret = sys::virDomainGetJobStats(self.as_ptr(),
+                                                &mut type_,
+                                                &mut params.ptr,
+                                                &mut params.nparams,
+                                                flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            let type_ = DomainJobType::from_int(type_).unwrap_or(DomainJobType::None);
+            return Ok(DomainJobStats::from_params(type_, &params));
+        }
+    }
+
+    /// Request that the current background job be aborted at the
+    /// soonest opportunity.
+    pub fn abort_job(&self) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainAbortJob(self.as_ptr()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Freeze the filesystems of the domain using the guest agent.
+    ///
+    /// If `mountpoints` is empty, all mounted filesystems are frozen.
+    /// Returns the number of frozen filesystems.
+    pub fn fs_freeze(&self, mountpoints: &[&str]) -> Result<u32, Error> {
+        let mountpoints: Vec<CString> = mountpoints.iter().map(|m| CString::new(*m).unwrap()).collect();
+        let mut ptrs: Vec<*const libc::c_char> = mountpoints.iter().map(|m| m.as_ptr()).collect();
+        unsafe {
+            let ret = sys::virDomainFSFreeze(self.as_ptr(),
+                                             if ptrs.is_empty() { ptr::null_mut() } else { ptrs.as_mut_ptr() },
+                                             ptrs.len() as libc::c_uint,
+                                             0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(ret as u32);
+        }
+    }
+
+    /// Thaw filesystems previously frozen by `fs_freeze()`.
+    ///
+    /// If `mountpoints` is empty, all frozen filesystems are thawed.
+    /// Returns the number of thawed filesystems.
+    pub fn fs_thaw(&self, mountpoints: &[&str]) -> Result<u32, Error> {
+        let mountpoints: Vec<CString> = mountpoints.iter().map(|m| CString::new(*m).unwrap()).collect();
+        let mut ptrs: Vec<*const libc::c_char> = mountpoints.iter().map(|m| m.as_ptr()).collect();
+        unsafe {
+            let ret = sys::virDomainFSThaw(self.as_ptr(),
+                                           if ptrs.is_empty() { ptr::null_mut() } else { ptrs.as_mut_ptr() },
+                                           ptrs.len() as libc::c_uint,
+                                           0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(ret as u32);
+        }
+    }
+
+    /// Discard unused blocks of the filesystem mounted at
+    /// `mountpoint`, or of all filesystems if `None`. Free ranges
+    /// shorter than `minimum` bytes may be ignored.
+    pub fn fs_trim(&self, mountpoint: Option<&str>, minimum: u64) -> Result<(), Error> {
+        let mountpoint = mountpoint.map(|m| CString::new(m).unwrap());
+        unsafe {
+            let mp = match mountpoint {
+                Some(ref m) => m.as_ptr(),
+                None => ptr::null(),
+            };
+            if sys::virDomainFSTrim(self.as_ptr(), mp, minimum as libc::c_ulonglong, 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Get a list of mapping information for each mounted filesystem
+    /// of the guest, using the guest agent.
+    pub fn get_fs_info(&self) -> Result<Vec<DomainFsInfo>, Error> {
+        unsafe {
+            let mut info: *mut sys::virDomainFSInfoPtr = ptr::null_mut();
+            let size = sys::virDomainGetFSInfo(self.as_ptr(), &mut info, 0);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<DomainFsInfo> = Vec::new();
+            for x in 0..size as isize {
+                let fs = *info.offset(x);
+                array.push(DomainFsInfo::from_ptr(fs));
+                sys::virDomainFSInfoFree(fs);
+            }
+            libc::free(info as *mut libc::c_void);
+
+            return Ok(array);
+        }
+    }
+
+    /// Set the password of `user` inside the guest, using the guest
+    /// agent.
+    pub fn set_user_password(&self, user: &str, password: &str, flags: DomainSetUserPasswordFlags) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainSetUserPassword(self.as_ptr(),
+                                                    string_to_c_chars!(user),
+                                                    string_to_c_chars!(password),
+                                                    flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Get the guest's notion of the current time, as seconds and
+    /// nanoseconds since the epoch.
+    pub fn get_time(&self) -> Result<(i64, u32), Error> {
+        unsafe {
+            let mut seconds: libc::c_longlong = 0;
+            let mut nseconds: libc::c_uint = 0;
+            if sys::virDomainGetTime(self.as_ptr(), &mut seconds, &mut nseconds, 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok((seconds as i64, nseconds as u32));
+        }
+    }
+
+    /// Set the guest's time. With `DomainSetTimeFlags::SYNC`, the
+    /// guest re-reads its time from the RTC and the given values are
+    /// ignored.
+    pub fn set_time(&self, seconds: i64, nseconds: u32, flags: DomainSetTimeFlags) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainSetTime(self.as_ptr(),
+                                            seconds as libc::c_longlong,
+                                            nseconds as libc::c_uint,
+                                            flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Query the guest agent for the state of the guest's vCPUs.
+    pub fn get_guest_vcpus(&self) -> Result<GuestVcpus, Error> {
+        unsafe {
+            let mut params = TypedParams::new();
+            let mut nparams: libc::c_uint = 0;
+            let ret = sys::virDomainGetGuestVcpus(self.as_ptr(), &mut params.ptr, &mut nparams, 0);
+            params.nparams = nparams as libc::c_int;
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(GuestVcpus {
+                vcpus: params.get_string("vcpus").unwrap_or_default(),
+                online: params.get_string("online").unwrap_or_default(),
+                offlinable: params.get_string("offlinable").unwrap_or_default(),
+            });
+        }
+    }
+
+    /// Enable or disable the guest vCPUs in `cpumap` (e.g. "1-3,5")
+    /// using the guest agent.
+    pub fn set_guest_vcpus(&self, cpumap: &str, state: bool) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainSetGuestVcpus(self.as_ptr(),
+                                                  string_to_c_chars!(cpumap),
+                                                  state as libc::c_int,
+                                                  0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Query the guest agent for various information about the guest
+    /// system. Passing an empty `types` requests every category the
+    /// agent supports.
+    pub fn get_guest_info(&self, types: DomainGuestInfoTypes) -> Result<GuestInfo, Error> {
+        unsafe {
+            let mut params = TypedParams::new();
+            let ret = sys::virDomainGetGuestInfo(self.as_ptr(),
+                                                 types.bits(),
+                                                 &mut params.ptr,
+                                                 &mut params.nparams,
+                                                 0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(GuestInfo::from_params(&params));
+        }
+    }
+
+    /// Fetch the SSH public keys authorized for `user` in the guest,
+    /// using the guest agent.
+    pub fn authorized_ssh_keys_get(&self, user: &str) -> Result<Vec<String>, Error> {
+        unsafe {
+            let mut keys: *mut *mut libc::c_char = ptr::null_mut();
+            let size = sys::virDomainAuthorizedSSHKeysGet(self.as_ptr(), string_to_c_chars!(user), &mut keys, 0);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<String> = Vec::new();
+            for x in 0..size as isize {
+                array.push(c_chars_to_string!(*keys.offset(x)));
+            }
+            libc::free(keys as *mut libc::c_void);
+
+            return Ok(array);
+        }
+    }
+
+    /// Replace, append to or remove from the SSH public keys
+    /// authorized for `user` in the guest, using the guest agent.
+    pub fn authorized_ssh_keys_set(&self, user: &str, keys: &[&str], flags: DomainAuthorizedSshKeysFlags) -> Result<(), Error> {
+        let keys: Vec<CString> = keys.iter().map(|k| CString::new(*k).unwrap()).collect();
+        let mut ptrs: Vec<*const libc::c_char> = keys.iter().map(|k| k.as_ptr()).collect();
+        unsafe {
+            let ret = sys::virDomainAuthorizedSSHKeysSet(self.as_ptr(),
+                                                         string_to_c_chars!(user),
+                                                         ptrs.as_mut_ptr(),
+                                                         ptrs.len() as libc::c_uint,
+                                                         flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Send key presses to the guest. Up to 16 `keycodes` from the
+    /// given keycode set are pressed together and held for `holdtime`
+    /// milliseconds (0 uses the hypervisor's default).
+    pub fn send_key(&self, codeset: KeycodeSet, holdtime: u32, keycodes: &[u32]) -> Result<(), Error> {
+        let mut keycodes: Vec<libc::c_uint> = keycodes.iter().map(|k| *k as libc::c_uint).collect();
+        unsafe {
+            let ret = sys::virDomainSendKey(self.as_ptr(),
+                                            codeset as libc::c_uint,
+                                            holdtime as libc::c_uint,
+                                            keycodes.as_mut_ptr(),
+                                            keycodes.len() as libc::c_int,
+                                            0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Type `text` into the guest, one key press at a time. See
+    /// `keycodes::linux_keycodes_from_ascii()` for which characters
+    /// are supported. Fails without sending anything if `text`
+    /// contains any other character.
+    pub fn send_text(&self, text: &str, holdtime: u32) -> Result<(), Error> {
+        let presses = match keycodes::linux_keycodes_from_ascii(text) {
+            Some(p) => p,
+            None => {
+                return Err(Error::new(ErrorNumber::InvalidArg,
+                                      ErrorDomain::Domain,
+                                      "text contains characters which can't be typed"));
+            }
+        };
+        for keys in presses {
+            self.send_key(KeycodeSet::Linux, holdtime, &keys)?;
+        }
+        return Ok(());
+    }
+
+    /// Send a signal to the guest process `pid`. Process IDs are
+    /// specific to the guest, not the host.
+    pub fn send_process_signal(&self, pid: i64, signal: DomainProcessSignal) -> Result<(), Error> {
+        unsafe {
+            let ret = sys::virDomainSendProcessSignal(self.as_ptr(),
+                                                      pid as libc::c_longlong,
+                                                      signal as libc::c_uint,
+                                                      0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Send a non-maskable interrupt to the guest.
+    pub fn inject_nmi(&self) -> Result<(), Error> {
+        unsafe {
+            if sys::virDomainInjectNMI(self.as_ptr(), 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Set the metadata of the given type. Passing `None` as
+    /// `metadata` removes it.
+    ///
+    /// For `DomainMetadataType::Element`, `metadata` is a single XML
+    /// element which is stored under the namespace `uri` using the
+    /// prefix `key`; other types take plain text and ignore `key` and
+    /// `uri`.
+    pub fn set_metadata(&self,
+                        type_: DomainMetadataType,
+                        metadata: Option<&str>,
+                        key: Option<&str>,
+                        uri: Option<&str>,
+                        flags: DomainModificationImpact)
+                        -> Result<(), Error> {
+        let metadata = metadata.map(|m| CString::new(m).unwrap());
+        let key = key.map(|k| CString::new(k).unwrap());
+        let uri = uri.map(|u| CString::new(u).unwrap());
+        unsafe {
+            let ret = sys::virDomainSetMetadata(self.as_ptr(),
+                                                type_ as libc::c_int,
+                                                metadata.as_ref().map_or(ptr::null(), |m| m.as_ptr()),
+                                                key.as_ref().map_or(ptr::null(), |k| k.as_ptr()),
+                                                uri.as_ref().map_or(ptr::null(), |u| u.as_ptr()),
+                                                flags.bits());
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Retrieve the metadata of the given type. For
+    /// `DomainMetadataType::Element`, `uri` selects the namespace of
+    /// the element to return.
+    pub fn get_metadata(&self,
+                        type_: DomainMetadataType,
+                        uri: Option<&str>,
+                        flags: DomainModificationImpact)
+                        -> Result<String, Error> {
+        let uri = uri.map(|u| CString::new(u).unwrap());
+        unsafe {
+            let n = sys::virDomainGetMetadata(self.as_ptr(),
+                                              type_ as libc::c_int,
+                                              uri.as_ref().map_or(ptr::null(), |u| u.as_ptr()),
+                                              flags.bits());
+            if n.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(n));
+        }
+    }
+
     pub fn interface_addresses(&self, source: InterfaceAddressSource) -> Result<Vec<DomainInterface>, Error> {
         let mut interfaces: Vec<DomainInterface> = Vec::new();
         let mut iface_ptr: *mut sys::virDomainInterfacePtr = ptr::null_mut();
@@ -678,4 +1883,119 @@ impl Domain {
 
         Ok(interfaces)
     }
+
+    /// Get size information about the disk `disk`, given as a target
+    /// such as "vda" or a source path.
+    pub fn get_block_info(&self, disk: &str) -> Result<DomainBlockInfo, Error> {
+        let disk = CString::new(disk).unwrap();
+        unsafe {
+            let pinfo = &mut sys::virDomainBlockInfo::default();
+            if sys::virDomainGetBlockInfo(self.as_ptr(), disk.as_ptr(), pinfo, 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(DomainBlockInfo::from_ptr(pinfo));
+        }
+    }
+
+    /// Read `size` bytes at `offset` from the disk `disk`. This reads
+    /// the storage file on the host, not what the guest sees, and the
+    /// QEMU driver refuses disks whose format isn't raw. Remote
+    /// connections limit a single read to 64 KiB; use
+    /// `block_chunks()` to read more.
+    pub fn block_peek(&self, disk: &str, offset: u64, size: usize) -> Result<Vec<u8>, Error> {
+        let disk = CString::new(disk).unwrap();
+        let mut buf: Vec<u8> = vec![0; size];
+        unsafe {
+            if sys::virDomainBlockPeek(self.as_ptr(),
+                                       disk.as_ptr(),
+                                       offset as libc::c_ulonglong,
+                                       size as libc::size_t,
+                                       buf.as_mut_ptr() as *mut libc::c_void,
+                                       0) == -1 {
+                return Err(Error::last_error());
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Iterate over the whole disk `disk` in reads of `chunk_size`
+    /// bytes, with `block_peek()`. The last chunk may be shorter.
+    /// `chunk_size` must not be 0, and on remote connections it must
+    /// be at most 64 KiB or every read fails.
+    pub fn block_chunks<'a>(&'a self, disk: &str, chunk_size: usize) -> Result<BlockChunks<'a>, Error> {
+        if chunk_size == 0 {
+            return Err(Error::new(ErrorNumber::InvalidArg, ErrorDomain::Domain, "chunk size must not be 0"));
+        }
+        let capacity = self.get_block_info(disk)?.capacity;
+        Ok(BlockChunks {
+            dom: self,
+            disk: disk.to_string(),
+            offset: 0,
+            end: capacity,
+            chunk_size: chunk_size,
+        })
+    }
+
+    /// Read `size` bytes of guest memory at `start`, which is a
+    /// virtual or physical address depending on `address`. Remote
+    /// connections limit a single read to 64 KiB.
+    pub fn memory_peek(&self, start: u64, size: usize, address: DomainMemoryPeekAddress) -> Result<Vec<u8>, Error> {
+        let mut buf: Vec<u8> = vec![0; size];
+        unsafe {
+            if sys::virDomainMemoryPeek(self.as_ptr(),
+                                        start as libc::c_ulonglong,
+                                        size as libc::size_t,
+                                        buf.as_mut_ptr() as *mut libc::c_void,
+                                        address as libc::c_uint) == -1 {
+                return Err(Error::last_error());
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Resize the disk `disk` to `size`, in KiB unless `flags`
+    /// contains `BYTES`.
+    pub fn block_resize(&self, disk: &str, size: u64, flags: DomainBlockResizeFlags) -> Result<(), Error> {
+        let disk = CString::new(disk).unwrap();
+        unsafe {
+            if sys::virDomainBlockResize(self.as_ptr(), disk.as_ptr(), size as libc::c_ulonglong, flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+}
+
+/// Reads a whole disk in chunks, see `Domain::block_chunks()`.
+pub struct BlockChunks<'a> {
+    dom: &'a Domain,
+    disk: String,
+    offset: u64,
+    end: u64,
+    chunk_size: usize,
+}
+
+impl<'a> BlockChunks<'a> {
+    /// Offset of the next chunk.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for BlockChunks<'a> {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.end {
+            return None;
+        }
+        let size = ::std::cmp::min(self.chunk_size as u64, self.end - self.offset) as usize;
+        let chunk = self.dom.block_peek(&self.disk, self.offset, size);
+        match chunk {
+            Ok(_) => self.offset += size as u64,
+            // Stop after reporting the error.
+            Err(_) => self.offset = self.end,
+        }
+        Some(chunk)
+    }
 }
@@ -0,0 +1,621 @@
+//! Typed reasons for domain states and details for lifecycle events.
+//!
+//! libvirt reports both as plain integers whose meaning depends on the
+//! state or event they belong to. The enums here each carry an
+//! `Other` variant for values added in newer libvirt releases.
+
+use domain::DomainState;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoStateReason {
+    Unknown,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl NoStateReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => NoStateReason::Unknown,
+            n => NoStateReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunningReason {
+    Unknown,
+    /// Normal startup from boot
+    Booted,
+    /// Migrated from another host
+    Migrated,
+    /// Restored from a state file
+    Restored,
+    /// Restored from snapshot
+    FromSnapshot,
+    /// Returned from paused state
+    Unpaused,
+    /// Returned from migration
+    MigrationCanceled,
+    /// Returned from failed save process
+    SaveCanceled,
+    /// Returned from pmsuspended due to wakeup event
+    Wakeup,
+    /// Resumed from crashed
+    Crashed,
+    /// Running in post-copy migration mode
+    Postcopy,
+    /// Running in failed post-copy migration
+    PostcopyFailed,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl RunningReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => RunningReason::Unknown,
+            1 => RunningReason::Booted,
+            2 => RunningReason::Migrated,
+            3 => RunningReason::Restored,
+            4 => RunningReason::FromSnapshot,
+            5 => RunningReason::Unpaused,
+            6 => RunningReason::MigrationCanceled,
+            7 => RunningReason::SaveCanceled,
+            8 => RunningReason::Wakeup,
+            9 => RunningReason::Crashed,
+            10 => RunningReason::Postcopy,
+            11 => RunningReason::PostcopyFailed,
+            n => RunningReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockedReason {
+    Unknown,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl BlockedReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => BlockedReason::Unknown,
+            n => BlockedReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PausedReason {
+    Unknown,
+    /// Paused on user request
+    User,
+    /// Paused for offline migration
+    Migration,
+    /// Paused for save
+    Save,
+    /// Paused for offline core dump
+    Dump,
+    /// Paused due to a disk I/O error
+    IoError,
+    /// Paused due to a watchdog event
+    Watchdog,
+    /// Paused after restoring from snapshot
+    FromSnapshot,
+    /// Paused during shutdown process
+    ShuttingDown,
+    /// Paused while creating a snapshot
+    Snapshot,
+    /// Paused due to a guest crash
+    Crashed,
+    /// The domain is being started
+    StartingUp,
+    /// Paused for post-copy migration
+    Postcopy,
+    /// Paused after failed post-copy
+    PostcopyFailed,
+    /// Paused after an error in an API call
+    ApiError,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl PausedReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => PausedReason::Unknown,
+            1 => PausedReason::User,
+            2 => PausedReason::Migration,
+            3 => PausedReason::Save,
+            4 => PausedReason::Dump,
+            5 => PausedReason::IoError,
+            6 => PausedReason::Watchdog,
+            7 => PausedReason::FromSnapshot,
+            8 => PausedReason::ShuttingDown,
+            9 => PausedReason::Snapshot,
+            10 => PausedReason::Crashed,
+            11 => PausedReason::StartingUp,
+            12 => PausedReason::Postcopy,
+            13 => PausedReason::PostcopyFailed,
+            14 => PausedReason::ApiError,
+            n => PausedReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShutdownReason {
+    Unknown,
+    /// Shutting down on user request
+    User,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl ShutdownReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => ShutdownReason::Unknown,
+            1 => ShutdownReason::User,
+            n => ShutdownReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShutoffReason {
+    Unknown,
+    /// Normal shutdown
+    Shutdown,
+    /// Forced poweroff
+    Destroyed,
+    /// Domain crashed
+    Crashed,
+    /// Migrated to another host
+    Migrated,
+    /// Saved to a file
+    Saved,
+    /// Domain failed to start
+    Failed,
+    /// Restored from a snapshot which was taken while the domain was shutoff
+    FromSnapshot,
+    /// Daemon decides to kill the domain during reconnection processing
+    Daemon,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl ShutoffReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => ShutoffReason::Unknown,
+            1 => ShutoffReason::Shutdown,
+            2 => ShutoffReason::Destroyed,
+            3 => ShutoffReason::Crashed,
+            4 => ShutoffReason::Migrated,
+            5 => ShutoffReason::Saved,
+            6 => ShutoffReason::Failed,
+            7 => ShutoffReason::FromSnapshot,
+            8 => ShutoffReason::Daemon,
+            n => ShutoffReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrashedReason {
+    Unknown,
+    /// Domain crashed due to a guest panic
+    Panicked,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl CrashedReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => CrashedReason::Unknown,
+            1 => CrashedReason::Panicked,
+            n => CrashedReason::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PmSuspendedReason {
+    Unknown,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl PmSuspendedReason {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => PmSuspendedReason::Unknown,
+            n => PmSuspendedReason::Other(n),
+        }
+    }
+}
+
+/// The reason a domain is in its current state, as returned by
+/// `Domain::get_state()`. The variant always matches the state it was
+/// reported with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainStateReason {
+    NoState(NoStateReason),
+    Running(RunningReason),
+    Blocked(BlockedReason),
+    Paused(PausedReason),
+    Shutdown(ShutdownReason),
+    Shutoff(ShutoffReason),
+    Crashed(CrashedReason),
+    PmSuspended(PmSuspendedReason),
+}
+
+impl DomainStateReason {
+    pub fn from_int(state: &DomainState, reason: i32) -> Self {
+        match *state {
+            DomainState::NoState => DomainStateReason::NoState(NoStateReason::from_int(reason)),
+            DomainState::Running => DomainStateReason::Running(RunningReason::from_int(reason)),
+            DomainState::Blocked => DomainStateReason::Blocked(BlockedReason::from_int(reason)),
+            DomainState::Paused => DomainStateReason::Paused(PausedReason::from_int(reason)),
+            DomainState::Shutdown => DomainStateReason::Shutdown(ShutdownReason::from_int(reason)),
+            DomainState::Shutoff => DomainStateReason::Shutoff(ShutoffReason::from_int(reason)),
+            DomainState::Crashed => DomainStateReason::Crashed(CrashedReason::from_int(reason)),
+            DomainState::PmSuspended => DomainStateReason::PmSuspended(PmSuspendedReason::from_int(reason)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DefinedDetail {
+    /// Newly created config file
+    Added,
+    /// Changed config file
+    Updated,
+    /// Domain was renamed
+    Renamed,
+    /// Config was restored from a snapshot
+    FromSnapshot,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl DefinedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => DefinedDetail::Added,
+            1 => DefinedDetail::Updated,
+            2 => DefinedDetail::Renamed,
+            3 => DefinedDetail::FromSnapshot,
+            n => DefinedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UndefinedDetail {
+    /// Deleted the config file
+    Removed,
+    /// Domain was renamed
+    Renamed,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl UndefinedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => UndefinedDetail::Removed,
+            1 => UndefinedDetail::Renamed,
+            n => UndefinedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartedDetail {
+    /// Normal startup from boot
+    Booted,
+    /// Incoming migration from another host
+    Migrated,
+    /// Restored from a state file
+    Restored,
+    /// Restored from snapshot
+    FromSnapshot,
+    /// Started due to wakeup event
+    Wakeup,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl StartedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => StartedDetail::Booted,
+            1 => StartedDetail::Migrated,
+            2 => StartedDetail::Restored,
+            3 => StartedDetail::FromSnapshot,
+            4 => StartedDetail::Wakeup,
+            n => StartedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SuspendedDetail {
+    /// Normal suspend due to admin pause
+    Paused,
+    /// Suspended for offline migration
+    Migrated,
+    /// Suspended due to a disk I/O error
+    IoError,
+    /// Suspended due to a watchdog firing
+    Watchdog,
+    /// Restored from paused state file
+    Restored,
+    /// Restored from paused snapshot
+    FromSnapshot,
+    /// Suspended after failure during libvirt API call
+    ApiError,
+    /// Suspended for post-copy migration
+    Postcopy,
+    /// Suspended after failed post-copy
+    PostcopyFailed,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl SuspendedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => SuspendedDetail::Paused,
+            1 => SuspendedDetail::Migrated,
+            2 => SuspendedDetail::IoError,
+            3 => SuspendedDetail::Watchdog,
+            4 => SuspendedDetail::Restored,
+            5 => SuspendedDetail::FromSnapshot,
+            6 => SuspendedDetail::ApiError,
+            7 => SuspendedDetail::Postcopy,
+            8 => SuspendedDetail::PostcopyFailed,
+            n => SuspendedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResumedDetail {
+    /// Normal resume due to admin unpause
+    Unpaused,
+    /// Resumed for completion of migration
+    Migrated,
+    /// Resumed from snapshot
+    FromSnapshot,
+    /// Resumed, but migration is still running in post-copy mode
+    Postcopy,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl ResumedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => ResumedDetail::Unpaused,
+            1 => ResumedDetail::Migrated,
+            2 => ResumedDetail::FromSnapshot,
+            3 => ResumedDetail::Postcopy,
+            n => ResumedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StoppedDetail {
+    /// Normal shutdown
+    Shutdown,
+    /// Forced poweroff from host
+    Destroyed,
+    /// Guest crashed
+    Crashed,
+    /// Migrated off to another host
+    Migrated,
+    /// Saved to a state file
+    Saved,
+    /// Host emulator/mgmt failed
+    Failed,
+    /// Offline snapshot loaded
+    FromSnapshot,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl StoppedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => StoppedDetail::Shutdown,
+            1 => StoppedDetail::Destroyed,
+            2 => StoppedDetail::Crashed,
+            3 => StoppedDetail::Migrated,
+            4 => StoppedDetail::Saved,
+            5 => StoppedDetail::Failed,
+            6 => StoppedDetail::FromSnapshot,
+            n => StoppedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShutdownDetail {
+    /// Guest finished shutdown sequence
+    Finished,
+    /// Domain finished shutting down after request from the guest itself
+    Guest,
+    /// Domain finished shutting down after request from the host
+    Host,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl ShutdownDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => ShutdownDetail::Finished,
+            1 => ShutdownDetail::Guest,
+            2 => ShutdownDetail::Host,
+            n => ShutdownDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PmSuspendedDetail {
+    /// Guest was PM suspended to memory
+    Memory,
+    /// Guest was PM suspended to disk
+    Disk,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl PmSuspendedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => PmSuspendedDetail::Memory,
+            1 => PmSuspendedDetail::Disk,
+            n => PmSuspendedDetail::Other(n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrashedDetail {
+    /// Guest was panicked
+    Panicked,
+    /// A value not known to this version of the bindings
+    Other(i32),
+}
+
+impl CrashedDetail {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => CrashedDetail::Panicked,
+            n => CrashedDetail::Other(n),
+        }
+    }
+}
+
+/// A domain lifecycle event together with its typed detail, as
+/// delivered to callbacks registered with
+/// `Connect::domain_event_lifecycle_register()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainLifecycleEvent {
+    Defined(DefinedDetail),
+    Undefined(UndefinedDetail),
+    Started(StartedDetail),
+    Suspended(SuspendedDetail),
+    Resumed(ResumedDetail),
+    Stopped(StoppedDetail),
+    Shutdown(ShutdownDetail),
+    PmSuspended(PmSuspendedDetail),
+    Crashed(CrashedDetail),
+    /// An event type not known to this version of the bindings,
+    /// with its raw detail
+    Other(i32, i32),
+}
+
+impl DomainLifecycleEvent {
+    pub fn from_int(event: i32, detail: i32) -> Self {
+        match event {
+            0 => DomainLifecycleEvent::Defined(DefinedDetail::from_int(detail)),
+            1 => DomainLifecycleEvent::Undefined(UndefinedDetail::from_int(detail)),
+            2 => DomainLifecycleEvent::Started(StartedDetail::from_int(detail)),
+            3 => DomainLifecycleEvent::Suspended(SuspendedDetail::from_int(detail)),
+            4 => DomainLifecycleEvent::Resumed(ResumedDetail::from_int(detail)),
+            5 => DomainLifecycleEvent::Stopped(StoppedDetail::from_int(detail)),
+            6 => DomainLifecycleEvent::Shutdown(ShutdownDetail::from_int(detail)),
+            7 => DomainLifecycleEvent::PmSuspended(PmSuspendedDetail::from_int(detail)),
+            8 => DomainLifecycleEvent::Crashed(CrashedDetail::from_int(detail)),
+            e => DomainLifecycleEvent::Other(e, detail),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::DomainState;
+
+    #[test]
+    fn state_reasons() {
+        assert_eq!(DomainStateReason::from_int(&DomainState::NoState, 0),
+                   DomainStateReason::NoState(NoStateReason::Unknown));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Running, 1),
+                   DomainStateReason::Running(RunningReason::Booted));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Running, 11),
+                   DomainStateReason::Running(RunningReason::PostcopyFailed));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Blocked, 0),
+                   DomainStateReason::Blocked(BlockedReason::Unknown));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Paused, 5),
+                   DomainStateReason::Paused(PausedReason::IoError));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Paused, 14),
+                   DomainStateReason::Paused(PausedReason::ApiError));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Shutdown, 1),
+                   DomainStateReason::Shutdown(ShutdownReason::User));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Shutoff, 2),
+                   DomainStateReason::Shutoff(ShutoffReason::Destroyed));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Shutoff, 8),
+                   DomainStateReason::Shutoff(ShutoffReason::Daemon));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Crashed, 1),
+                   DomainStateReason::Crashed(CrashedReason::Panicked));
+        assert_eq!(DomainStateReason::from_int(&DomainState::PmSuspended, 0),
+                   DomainStateReason::PmSuspended(PmSuspendedReason::Unknown));
+    }
+
+    #[test]
+    fn unknown_state_reasons() {
+        assert_eq!(DomainStateReason::from_int(&DomainState::NoState, 1),
+                   DomainStateReason::NoState(NoStateReason::Other(1)));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Running, 12),
+                   DomainStateReason::Running(RunningReason::Other(12)));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Paused, 15),
+                   DomainStateReason::Paused(PausedReason::Other(15)));
+        assert_eq!(DomainStateReason::from_int(&DomainState::Shutoff, 9),
+                   DomainStateReason::Shutoff(ShutoffReason::Other(9)));
+        assert_eq!(CrashedReason::from_int(-1), CrashedReason::Other(-1));
+    }
+
+    #[test]
+    fn lifecycle_events() {
+        assert_eq!(DomainLifecycleEvent::from_int(0, 2),
+                   DomainLifecycleEvent::Defined(DefinedDetail::Renamed));
+        assert_eq!(DomainLifecycleEvent::from_int(1, 0),
+                   DomainLifecycleEvent::Undefined(UndefinedDetail::Removed));
+        assert_eq!(DomainLifecycleEvent::from_int(2, 4),
+                   DomainLifecycleEvent::Started(StartedDetail::Wakeup));
+        assert_eq!(DomainLifecycleEvent::from_int(3, 2),
+                   DomainLifecycleEvent::Suspended(SuspendedDetail::IoError));
+        assert_eq!(DomainLifecycleEvent::from_int(4, 3),
+                   DomainLifecycleEvent::Resumed(ResumedDetail::Postcopy));
+        assert_eq!(DomainLifecycleEvent::from_int(5, 1),
+                   DomainLifecycleEvent::Stopped(StoppedDetail::Destroyed));
+        assert_eq!(DomainLifecycleEvent::from_int(6, 1),
+                   DomainLifecycleEvent::Shutdown(ShutdownDetail::Guest));
+        assert_eq!(DomainLifecycleEvent::from_int(7, 1),
+                   DomainLifecycleEvent::PmSuspended(PmSuspendedDetail::Disk));
+        assert_eq!(DomainLifecycleEvent::from_int(8, 0),
+                   DomainLifecycleEvent::Crashed(CrashedDetail::Panicked));
+    }
+
+    #[test]
+    fn unknown_lifecycle_events() {
+        assert_eq!(DomainLifecycleEvent::from_int(0, 4),
+                   DomainLifecycleEvent::Defined(DefinedDetail::Other(4)));
+        assert_eq!(DomainLifecycleEvent::from_int(5, 7),
+                   DomainLifecycleEvent::Stopped(StoppedDetail::Other(7)));
+        assert_eq!(DomainLifecycleEvent::from_int(8, 1),
+                   DomainLifecycleEvent::Crashed(CrashedDetail::Other(1)));
+        assert_eq!(DomainLifecycleEvent::from_int(9, 3), DomainLifecycleEvent::Other(9, 3));
+    }
+}
@@ -0,0 +1,1989 @@
+//! Typed model of the domain XML format.
+//!
+//! See: http://libvirt.org/formatdomain.html
+//!
+//! Only the commonly used parts of the schema are modelled as fields.
+//! Everything else is kept in the `extra` field of the closest
+//! enclosing struct, so `DomainConfig::from_xml(xml)?.to_xml()`
+//! describes the same domain as `xml`.
+
+use connect::Connect;
+use domain::Domain;
+use domain::flags::XmlFlags;
+use error::Error;
+use storage_vol::backing::BackingImage;
+use std::fmt;
+use std::str::FromStr;
+use xmlutil::*;
+
+pub use xmlutil::{Element, Extra, XmlModel};
+
+/// Unit of a memory or storage size, as used by the `unit` attribute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MemoryUnit {
+    Bytes,
+    KB,
+    KiB,
+    MB,
+    MiB,
+    GB,
+    GiB,
+    TB,
+    TiB,
+    PB,
+    PiB,
+    EB,
+    EiB,
+}
+
+impl MemoryUnit {
+    /// Number of bytes in one of this unit.
+    pub fn bytes(&self) -> u64 {
+        match *self {
+            MemoryUnit::Bytes => 1,
+            MemoryUnit::KB => 1000,
+            MemoryUnit::KiB => 1 << 10,
+            MemoryUnit::MB => 1000 * 1000,
+            MemoryUnit::MiB => 1 << 20,
+            MemoryUnit::GB => 1000 * 1000 * 1000,
+            MemoryUnit::GiB => 1 << 30,
+            MemoryUnit::TB => 1000 * 1000 * 1000 * 1000,
+            MemoryUnit::TiB => 1 << 40,
+            MemoryUnit::PB => 1000 * 1000 * 1000 * 1000 * 1000,
+            MemoryUnit::PiB => 1 << 50,
+            MemoryUnit::EB => 1000 * 1000 * 1000 * 1000 * 1000 * 1000,
+            MemoryUnit::EiB => 1 << 60,
+        }
+    }
+}
+
+impl FromStr for MemoryUnit {
+    type Err = ();
+
+    /// Parse a unit the way libvirt does: case insensitive, with "k",
+    /// "KiB" and the like for powers of 1024 and "KB" and the like for
+    /// powers of 1000.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        if s == "b" || s == "byte" || s == "bytes" {
+            return Ok(MemoryUnit::Bytes);
+        }
+        let binary = match s.get(1..) {
+            Some("") | Some("ib") => true,
+            Some("b") => false,
+            _ => return Err(()),
+        };
+        match (&s[..1], binary) {
+            ("k", true) => Ok(MemoryUnit::KiB),
+            ("k", false) => Ok(MemoryUnit::KB),
+            ("m", true) => Ok(MemoryUnit::MiB),
+            ("m", false) => Ok(MemoryUnit::MB),
+            ("g", true) => Ok(MemoryUnit::GiB),
+            ("g", false) => Ok(MemoryUnit::GB),
+            ("t", true) => Ok(MemoryUnit::TiB),
+            ("t", false) => Ok(MemoryUnit::TB),
+            ("p", true) => Ok(MemoryUnit::PiB),
+            ("p", false) => Ok(MemoryUnit::PB),
+            ("e", true) => Ok(MemoryUnit::EiB),
+            ("e", false) => Ok(MemoryUnit::EB),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for MemoryUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            MemoryUnit::Bytes => "bytes",
+            MemoryUnit::KB => "KB",
+            MemoryUnit::KiB => "KiB",
+            MemoryUnit::MB => "MB",
+            MemoryUnit::MiB => "MiB",
+            MemoryUnit::GB => "GB",
+            MemoryUnit::GiB => "GiB",
+            MemoryUnit::TB => "TB",
+            MemoryUnit::TiB => "TiB",
+            MemoryUnit::PB => "PB",
+            MemoryUnit::PiB => "PiB",
+            MemoryUnit::EB => "EB",
+            MemoryUnit::EiB => "EiB",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A size with an optional unit, such as `<memory unit='MiB'>2048</memory>`.
+#[derive(Clone, Debug)]
+pub struct Memory {
+    pub value: u64,
+    /// `None` means the element's default unit, which is KiB for
+    /// domain memory and bytes for storage sizes.
+    pub unit: Option<MemoryUnit>,
+    pub extra: Extra,
+    /// The unit as spelled in the parsed XML, such as "k" or "B".
+    unit_name: Option<String>,
+}
+
+impl PartialEq for Memory {
+    fn eq(&self, other: &Memory) -> bool {
+        self.value == other.value && self.unit == other.unit && self.extra == other.extra
+    }
+}
+
+impl Memory {
+    pub fn new(value: u64, unit: MemoryUnit) -> Memory {
+        Memory {
+            value: value,
+            unit: Some(unit),
+            extra: Extra::default(),
+            unit_name: None,
+        }
+    }
+
+    /// The size in bytes, using `default` when no unit is given. Fails
+    /// if the size doesn't fit in a `u64`.
+    pub fn to_bytes(&self, default: MemoryUnit) -> Result<u64, Error> {
+        let unit = self.unit.unwrap_or(default);
+        self.value
+            .checked_mul(unit.bytes())
+            .ok_or_else(|| xml_error(format!("size {} {} is too large", self.value, unit)))
+    }
+
+    /// The size in KiB, which is the default unit for domain memory.
+    pub fn to_kib(&self) -> Result<u64, Error> {
+        self.to_bytes(MemoryUnit::KiB).map(|b| b / 1024)
+    }
+
+    pub fn from_named(mut el: Element) -> Result<Memory, Error> {
+        let unit_name = take_attr(&mut el, "unit");
+        let unit = match unit_name {
+            Some(ref u) => Some(u.parse().map_err(|_| xml_error(format!("unknown unit '{}'", u)))?),
+            None => None,
+        };
+        let value = take_text(&mut el).unwrap_or_default();
+        let value = value.trim().parse().map_err(|_| xml_error(format!("invalid size '{}' in <{}>", value, el.name)))?;
+        Ok(Memory {
+            value: value,
+            unit: unit,
+            extra: Extra::take(el),
+            unit_name: unit_name,
+        })
+    }
+
+    pub fn to_named(&self, name: &str) -> Element {
+        let mut el = text_element(name, self.value);
+        // Keep the original spelling unless the unit was changed.
+        match self.unit_name {
+            Some(ref u) if u.parse().ok() == self.unit => set_attr(&mut el, "unit", &Some(u)),
+            _ => set_attr(&mut el, "unit", &self.unit),
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+pub fn take_memory(el: &mut Element, name: &str) -> Result<Option<Memory>, Error> {
+    match take_child(el, name) {
+        Some(c) => Memory::from_named(c).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn push_memory(el: &mut Element, name: &str, value: &Option<Memory>) {
+    if let Some(ref m) = *value {
+        push(el, m.to_named(name));
+    }
+}
+
+/// Virtual CPU allocation, `<vcpu>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vcpu {
+    /// Maximum number of vCPUs.
+    pub count: u32,
+    /// "static" or "auto".
+    pub placement: Option<String>,
+    /// Host CPUs the vCPUs may run on, such as "1-4,^3".
+    pub cpuset: Option<String>,
+    /// Number of vCPUs enabled at boot, if lower than `count`.
+    pub current: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Vcpu {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "vcpu")?;
+        let count = take_text(&mut el).unwrap_or_default();
+        let count = count.trim().parse().map_err(|_| xml_error(format!("invalid vcpu count '{}'", count)))?;
+        Ok(Vcpu {
+            count: count,
+            placement: take_attr(&mut el, "placement"),
+            cpuset: take_attr(&mut el, "cpuset"),
+            current: take_attr_parse(&mut el, "current")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = text_element("vcpu", self.count);
+        set_attr(&mut el, "placement", &self.placement);
+        set_attr(&mut el, "cpuset", &self.cpuset);
+        set_attr(&mut el, "current", &self.current);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<type>` inside `<os>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OsType {
+    /// "hvm", "linux", "exe" and so on.
+    pub value: String,
+    pub arch: Option<String>,
+    pub machine: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for OsType {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "type")?;
+        let value = take_text(&mut el).unwrap_or_default();
+        Ok(OsType {
+            value: value,
+            arch: take_attr(&mut el, "arch"),
+            machine: take_attr(&mut el, "machine"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = text_element("type", &self.value);
+        set_attr(&mut el, "arch", &self.arch);
+        set_attr(&mut el, "machine", &self.machine);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<loader>` inside `<os>`, for firmware such as OVMF.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loader {
+    pub path: String,
+    pub readonly: Option<bool>,
+    /// "rom" or "pflash".
+    pub loader_type: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Loader {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "loader")?;
+        let path = take_text(&mut el).unwrap_or_default();
+        Ok(Loader {
+            path: path,
+            readonly: take_attr_bool(&mut el, "readonly")?,
+            loader_type: take_attr(&mut el, "type"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = text_element("loader", &self.path);
+        set_attr_bool(&mut el, "readonly", self.readonly, "yes", "no");
+        set_attr(&mut el, "type", &self.loader_type);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Operating system booting, `<os>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Os {
+    pub os_type: OsType,
+    pub loader: Option<Loader>,
+    /// Boot devices in order of preference: "hd", "cdrom", "network"
+    /// or "fd".
+    pub boot: Vec<String>,
+    /// Direct kernel boot.
+    pub kernel: Option<String>,
+    pub initrd: Option<String>,
+    pub cmdline: Option<String>,
+    /// Path of the init binary, for container based domains.
+    pub init: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Os {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "os")?;
+        let os_type = match take_model(&mut el, "type")? {
+            Some(t) => t,
+            None => return Err(xml_error("missing <type> in <os>")),
+        };
+        let mut boot = Vec::new();
+        for mut b in take_children(&mut el, "boot") {
+            match take_attr(&mut b, "dev") {
+                Some(dev) => boot.push(dev),
+                None => return Err(xml_error("missing 'dev' attribute in <boot>")),
+            }
+        }
+        Ok(Os {
+            os_type: os_type,
+            loader: take_model(&mut el, "loader")?,
+            boot: boot,
+            kernel: take_child_text(&mut el, "kernel"),
+            initrd: take_child_text(&mut el, "initrd"),
+            cmdline: take_child_text(&mut el, "cmdline"),
+            init: take_child_text(&mut el, "init"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("os");
+        push(&mut el, self.os_type.to_element());
+        push_model(&mut el, &self.loader);
+        push_text(&mut el, "kernel", &self.kernel);
+        push_text(&mut el, "initrd", &self.initrd);
+        push_text(&mut el, "cmdline", &self.cmdline);
+        push_text(&mut el, "init", &self.init);
+        for dev in &self.boot {
+            let mut b = Element::new("boot");
+            set_attr(&mut b, "dev", &Some(dev));
+            push(&mut el, b);
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Hypervisor features, `<features>`. Features with settings, such
+/// as `<hyperv>`, are kept in `extra`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Features {
+    pub acpi: bool,
+    pub apic: bool,
+    pub pae: bool,
+    pub hap: bool,
+    pub privnet: bool,
+    pub extra: Extra,
+}
+
+impl XmlModel for Features {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "features")?;
+        Ok(Features {
+            acpi: take_flag(&mut el, "acpi"),
+            apic: take_flag(&mut el, "apic"),
+            pae: take_flag(&mut el, "pae"),
+            hap: take_flag(&mut el, "hap"),
+            privnet: take_flag(&mut el, "privnet"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("features");
+        push_flag(&mut el, "acpi", self.acpi);
+        push_flag(&mut el, "apic", self.apic);
+        push_flag(&mut el, "pae", self.pae);
+        push_flag(&mut el, "hap", self.hap);
+        push_flag(&mut el, "privnet", self.privnet);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<model>` inside `<cpu>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuModel {
+    pub name: String,
+    /// "allow" or "forbid".
+    pub fallback: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CpuModel {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "model")?;
+        let name = take_text(&mut el).unwrap_or_default();
+        Ok(CpuModel {
+            name: name,
+            fallback: take_attr(&mut el, "fallback"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = text_element("model", &self.name);
+        set_attr(&mut el, "fallback", &self.fallback);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<topology>` inside `<cpu>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuTopology {
+    pub sockets: Option<u32>,
+    pub dies: Option<u32>,
+    pub cores: Option<u32>,
+    pub threads: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CpuTopology {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "topology")?;
+        Ok(CpuTopology {
+            sockets: take_attr_parse(&mut el, "sockets")?,
+            dies: take_attr_parse(&mut el, "dies")?,
+            cores: take_attr_parse(&mut el, "cores")?,
+            threads: take_attr_parse(&mut el, "threads")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("topology");
+        set_attr(&mut el, "sockets", &self.sockets);
+        set_attr(&mut el, "dies", &self.dies);
+        set_attr(&mut el, "cores", &self.cores);
+        set_attr(&mut el, "threads", &self.threads);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<feature>` inside `<cpu>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuFeature {
+    /// "force", "require", "optional", "disable" or "forbid".
+    pub policy: Option<String>,
+    pub name: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CpuFeature {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "feature")?;
+        Ok(CpuFeature {
+            policy: take_attr(&mut el, "policy"),
+            name: take_attr(&mut el, "name"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("feature");
+        set_attr(&mut el, "policy", &self.policy);
+        set_attr(&mut el, "name", &self.name);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// CPU model and topology, `<cpu>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cpu {
+    /// "custom", "host-model" or "host-passthrough".
+    pub mode: Option<String>,
+    /// "minimum", "exact" or "strict".
+    pub match_: Option<String>,
+    /// "none", "partial" or "full".
+    pub check: Option<String>,
+    pub model: Option<CpuModel>,
+    pub vendor: Option<String>,
+    pub topology: Option<CpuTopology>,
+    pub features: Vec<CpuFeature>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Cpu {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "cpu")?;
+        Ok(Cpu {
+            mode: take_attr(&mut el, "mode"),
+            match_: take_attr(&mut el, "match"),
+            check: take_attr(&mut el, "check"),
+            model: take_model(&mut el, "model")?,
+            vendor: take_child_text(&mut el, "vendor"),
+            topology: take_model(&mut el, "topology")?,
+            features: take_models(&mut el, "feature")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("cpu");
+        set_attr(&mut el, "mode", &self.mode);
+        set_attr(&mut el, "match", &self.match_);
+        set_attr(&mut el, "check", &self.check);
+        push_model(&mut el, &self.model);
+        push_text(&mut el, "vendor", &self.vendor);
+        push_model(&mut el, &self.topology);
+        push_models(&mut el, &self.features);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<timer>` inside `<clock>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timer {
+    /// "rtc", "pit", "hpet", "kvmclock" and so on.
+    pub name: Option<String>,
+    pub present: Option<bool>,
+    /// "delay", "catchup", "merge" or "discard".
+    pub tickpolicy: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Timer {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "timer")?;
+        Ok(Timer {
+            name: take_attr(&mut el, "name"),
+            present: take_attr_bool(&mut el, "present")?,
+            tickpolicy: take_attr(&mut el, "tickpolicy"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("timer");
+        set_attr(&mut el, "name", &self.name);
+        set_attr_bool(&mut el, "present", self.present, "yes", "no");
+        set_attr(&mut el, "tickpolicy", &self.tickpolicy);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Time keeping, `<clock>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Clock {
+    /// "utc", "localtime", "timezone" or "variable".
+    pub offset: Option<String>,
+    pub timezone: Option<String>,
+    pub timers: Vec<Timer>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Clock {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "clock")?;
+        Ok(Clock {
+            offset: take_attr(&mut el, "offset"),
+            timezone: take_attr(&mut el, "timezone"),
+            timers: take_models(&mut el, "timer")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("clock");
+        set_attr(&mut el, "offset", &self.offset);
+        set_attr(&mut el, "timezone", &self.timezone);
+        push_models(&mut el, &self.timers);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<driver>` inside `<disk>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskDriver {
+    /// Hypervisor driver, such as "qemu".
+    pub name: Option<String>,
+    /// Image format, such as "raw" or "qcow2".
+    pub driver_type: Option<String>,
+    pub cache: Option<String>,
+    pub io: Option<String>,
+    pub discard: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for DiskDriver {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "driver")?;
+        Ok(DiskDriver {
+            name: take_attr(&mut el, "name"),
+            driver_type: take_attr(&mut el, "type"),
+            cache: take_attr(&mut el, "cache"),
+            io: take_attr(&mut el, "io"),
+            discard: take_attr(&mut el, "discard"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("driver");
+        set_attr(&mut el, "name", &self.name);
+        set_attr(&mut el, "type", &self.driver_type);
+        set_attr(&mut el, "cache", &self.cache);
+        set_attr(&mut el, "io", &self.io);
+        set_attr(&mut el, "discard", &self.discard);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<source>` inside `<disk>`. Which fields are used depends on the
+/// disk type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskSource {
+    /// For "file" disks.
+    pub file: Option<String>,
+    /// For "block" disks.
+    pub dev: Option<String>,
+    /// For "dir" disks.
+    pub dir: Option<String>,
+    /// For "volume" disks.
+    pub pool: Option<String>,
+    pub volume: Option<String>,
+    /// For "network" disks, such as "rbd" or "iscsi".
+    pub protocol: Option<String>,
+    pub name: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for DiskSource {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "source")?;
+        Ok(DiskSource {
+            file: take_attr(&mut el, "file"),
+            dev: take_attr(&mut el, "dev"),
+            dir: take_attr(&mut el, "dir"),
+            pool: take_attr(&mut el, "pool"),
+            volume: take_attr(&mut el, "volume"),
+            protocol: take_attr(&mut el, "protocol"),
+            name: take_attr(&mut el, "name"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        set_attr(&mut el, "file", &self.file);
+        set_attr(&mut el, "dev", &self.dev);
+        set_attr(&mut el, "dir", &self.dir);
+        set_attr(&mut el, "pool", &self.pool);
+        set_attr(&mut el, "volume", &self.volume);
+        set_attr(&mut el, "protocol", &self.protocol);
+        set_attr(&mut el, "name", &self.name);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<target>` inside `<disk>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskTarget {
+    /// Device name in the guest, such as "vda".
+    pub dev: Option<String>,
+    /// "virtio", "sata", "scsi", "ide" and so on.
+    pub bus: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for DiskTarget {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "target")?;
+        Ok(DiskTarget {
+            dev: take_attr(&mut el, "dev"),
+            bus: take_attr(&mut el, "bus"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("target");
+        set_attr(&mut el, "dev", &self.dev);
+        set_attr(&mut el, "bus", &self.bus);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Take the `name` of an `<alias>` child.
+fn take_alias(el: &mut Element) -> Option<String> {
+    take_child_attr(el, "alias", "name")
+}
+
+fn push_alias(el: &mut Element, alias: &Option<String>) {
+    push_child_attr(el, "alias", "name", alias);
+}
+
+/// Take the `order` of a `<boot>` child.
+fn take_boot_order(el: &mut Element) -> Result<Option<u32>, Error> {
+    match take_child_attr(el, "boot", "order") {
+        Some(v) => v.parse().map(Some).map_err(|_| xml_error(format!("invalid boot order '{}'", v))),
+        None => Ok(None),
+    }
+}
+
+fn push_boot_order(el: &mut Element, order: &Option<u32>) {
+    push_child_attr(el, "boot", "order", order);
+}
+
+/// The image a disk image is layered on, `<backingStore>`.
+///
+/// libvirt ends a known backing chain with an empty `<backingStore/>`,
+/// which parses as a `DiskBackingStore` with every field unset.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskBackingStore {
+    /// "file", "block", "network" and so on.
+    pub store_type: Option<String>,
+    /// Position in the chain, as used in block job targets like
+    /// "vda[1]".
+    pub index: Option<u32>,
+    pub format: Option<String>,
+    pub source: Option<DiskSource>,
+    pub backing_store: Option<Box<DiskBackingStore>>,
+    pub extra: Extra,
+}
+
+impl DiskBackingStore {
+    /// Whether this is the empty element ending a chain.
+    pub fn is_end(&self) -> bool {
+        *self == DiskBackingStore::default()
+    }
+}
+
+impl XmlModel for DiskBackingStore {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "backingStore")?;
+        let backing_store = match take_model(&mut el, "backingStore")? {
+            Some(b) => Some(Box::new(b)),
+            None => None,
+        };
+        Ok(DiskBackingStore {
+            store_type: take_attr(&mut el, "type"),
+            index: take_attr_parse(&mut el, "index")?,
+            format: take_child_attr(&mut el, "format", "type"),
+            source: take_model(&mut el, "source")?,
+            backing_store: backing_store,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("backingStore");
+        set_attr(&mut el, "type", &self.store_type);
+        set_attr(&mut el, "index", &self.index);
+        push_child_attr(&mut el, "format", "type", &self.format);
+        push_model(&mut el, &self.source);
+        if let Some(ref b) = self.backing_store {
+            push(&mut el, b.to_element());
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A disk, CD-ROM or floppy, `<disk>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Disk {
+    /// "file", "block", "dir", "network" or "volume".
+    pub disk_type: Option<String>,
+    /// "disk", "cdrom", "floppy" or "lun".
+    pub device: Option<String>,
+    pub driver: Option<DiskDriver>,
+    pub source: Option<DiskSource>,
+    pub target: Option<DiskTarget>,
+    /// Backing chain of the disk image, reported by libvirt for
+    /// running domains.
+    pub backing_store: Option<DiskBackingStore>,
+    pub readonly: bool,
+    pub shareable: bool,
+    pub serial: Option<String>,
+    pub boot_order: Option<u32>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Disk {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "disk")?;
+        Ok(Disk {
+            disk_type: take_attr(&mut el, "type"),
+            device: take_attr(&mut el, "device"),
+            driver: take_model(&mut el, "driver")?,
+            source: take_model(&mut el, "source")?,
+            target: take_model(&mut el, "target")?,
+            backing_store: take_model(&mut el, "backingStore")?,
+            readonly: take_flag(&mut el, "readonly"),
+            shareable: take_flag(&mut el, "shareable"),
+            serial: take_child_text(&mut el, "serial"),
+            boot_order: take_boot_order(&mut el)?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("disk");
+        set_attr(&mut el, "type", &self.disk_type);
+        set_attr(&mut el, "device", &self.device);
+        push_model(&mut el, &self.driver);
+        push_model(&mut el, &self.source);
+        push_model(&mut el, &self.target);
+        push_model(&mut el, &self.backing_store);
+        push_flag(&mut el, "readonly", self.readonly);
+        push_flag(&mut el, "shareable", self.shareable);
+        push_text(&mut el, "serial", &self.serial);
+        push_boot_order(&mut el, &self.boot_order);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<source>` inside `<interface>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceSource {
+    /// For "network" interfaces.
+    pub network: Option<String>,
+    /// For "bridge" interfaces.
+    pub bridge: Option<String>,
+    /// For "direct" interfaces.
+    pub dev: Option<String>,
+    /// macvtap mode of "direct" interfaces, such as "bridge".
+    pub mode: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for InterfaceSource {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "source")?;
+        Ok(InterfaceSource {
+            network: take_attr(&mut el, "network"),
+            bridge: take_attr(&mut el, "bridge"),
+            dev: take_attr(&mut el, "dev"),
+            mode: take_attr(&mut el, "mode"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        set_attr(&mut el, "network", &self.network);
+        set_attr(&mut el, "bridge", &self.bridge);
+        set_attr(&mut el, "dev", &self.dev);
+        set_attr(&mut el, "mode", &self.mode);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+impl Disk {
+    /// The backing chain of the disk image, from the image right below
+    /// the disk's source down to the base image.
+    pub fn backing_chain(&self) -> Vec<BackingImage> {
+        let mut chain = Vec::new();
+        let mut store = self.backing_store.as_ref();
+        while let Some(s) = store {
+            if s.is_end() {
+                break;
+            }
+            let path = s.source.as_ref().and_then(|src| {
+                src.file.clone().or_else(|| src.dev.clone()).or_else(|| src.name.clone())
+            });
+            chain.push(BackingImage {
+                path: path,
+                format: s.format.clone(),
+            });
+            store = s.backing_store.as_ref().map(|b| &**b);
+        }
+        chain
+    }
+}
+
+/// A network interface, `<interface>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interface {
+    /// "network", "bridge", "direct", "user" and so on.
+    pub interface_type: Option<String>,
+    pub mac: Option<String>,
+    pub source: Option<InterfaceSource>,
+    /// Device model, such as "virtio" or "e1000".
+    pub model: Option<String>,
+    /// Name of the host side device, such as "vnet0".
+    pub target: Option<String>,
+    pub boot_order: Option<u32>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Interface {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "interface")?;
+        Ok(Interface {
+            interface_type: take_attr(&mut el, "type"),
+            mac: take_child_attr(&mut el, "mac", "address"),
+            source: take_model(&mut el, "source")?,
+            model: take_child_attr(&mut el, "model", "type"),
+            target: take_child_attr(&mut el, "target", "dev"),
+            boot_order: take_boot_order(&mut el)?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("interface");
+        set_attr(&mut el, "type", &self.interface_type);
+        push_child_attr(&mut el, "mac", "address", &self.mac);
+        push_model(&mut el, &self.source);
+        push_child_attr(&mut el, "model", "type", &self.model);
+        push_child_attr(&mut el, "target", "dev", &self.target);
+        push_boot_order(&mut el, &self.boot_order);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A bus controller, `<controller>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Controller {
+    /// "pci", "usb", "scsi", "virtio-serial", "sata" and so on.
+    pub controller_type: Option<String>,
+    pub index: Option<u32>,
+    pub model: Option<String>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Controller {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "controller")?;
+        Ok(Controller {
+            controller_type: take_attr(&mut el, "type"),
+            index: take_attr_parse(&mut el, "index")?,
+            model: take_attr(&mut el, "model"),
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("controller");
+        set_attr(&mut el, "type", &self.controller_type);
+        set_attr(&mut el, "index", &self.index);
+        set_attr(&mut el, "model", &self.model);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A graphical framebuffer, `<graphics>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Graphics {
+    /// "vnc", "spice", "sdl", "rdp" and so on.
+    pub graphics_type: Option<String>,
+    /// TCP port; -1 asks for automatic allocation in older configs.
+    pub port: Option<i32>,
+    pub autoport: Option<bool>,
+    /// Address to listen on.
+    pub listen: Option<String>,
+    pub passwd: Option<String>,
+    pub keymap: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Graphics {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "graphics")?;
+        Ok(Graphics {
+            graphics_type: take_attr(&mut el, "type"),
+            port: take_attr_parse(&mut el, "port")?,
+            autoport: take_attr_bool(&mut el, "autoport")?,
+            listen: take_attr(&mut el, "listen"),
+            passwd: take_attr(&mut el, "passwd"),
+            keymap: take_attr(&mut el, "keymap"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("graphics");
+        set_attr(&mut el, "type", &self.graphics_type);
+        set_attr(&mut el, "port", &self.port);
+        set_attr_bool(&mut el, "autoport", self.autoport, "yes", "no");
+        set_attr(&mut el, "listen", &self.listen);
+        set_attr(&mut el, "passwd", &self.passwd);
+        set_attr(&mut el, "keymap", &self.keymap);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<source>` inside a character device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharSource {
+    /// "bind" or "connect".
+    pub mode: Option<String>,
+    pub path: Option<String>,
+    pub host: Option<String>,
+    pub service: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CharSource {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "source")?;
+        Ok(CharSource {
+            mode: take_attr(&mut el, "mode"),
+            path: take_attr(&mut el, "path"),
+            host: take_attr(&mut el, "host"),
+            service: take_attr(&mut el, "service"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        set_attr(&mut el, "mode", &self.mode);
+        set_attr(&mut el, "path", &self.path);
+        set_attr(&mut el, "host", &self.host);
+        set_attr(&mut el, "service", &self.service);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<target>` inside a character device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharTarget {
+    /// "serial", "virtio", "guestfwd" and so on.
+    pub target_type: Option<String>,
+    pub port: Option<u32>,
+    /// Channel name, such as "org.qemu.guest_agent.0".
+    pub name: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CharTarget {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "target")?;
+        Ok(CharTarget {
+            target_type: take_attr(&mut el, "type"),
+            port: take_attr_parse(&mut el, "port")?,
+            name: take_attr(&mut el, "name"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("target");
+        set_attr(&mut el, "type", &self.target_type);
+        set_attr(&mut el, "port", &self.port);
+        set_attr(&mut el, "name", &self.name);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A character device, used for `<serial>`, `<console>` and
+/// `<channel>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharDevice {
+    /// Element name: "serial", "console" or "channel".
+    pub kind: String,
+    /// "pty", "unix", "spicevmc", "file" and so on.
+    pub device_type: Option<String>,
+    pub source: Option<CharSource>,
+    pub target: Option<CharTarget>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for CharDevice {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        let kind = el.name.clone();
+        Ok(CharDevice {
+            kind: kind,
+            device_type: take_attr(&mut el, "type"),
+            source: take_model(&mut el, "source")?,
+            target: take_model(&mut el, "target")?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new(&self.kind);
+        set_attr(&mut el, "type", &self.device_type);
+        push_model(&mut el, &self.source);
+        push_model(&mut el, &self.target);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A PCI, USB or drive address.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Address {
+    /// "pci", "drive", "usb" and so on.
+    pub address_type: Option<String>,
+    pub domain: Option<String>,
+    pub bus: Option<String>,
+    pub slot: Option<String>,
+    pub function: Option<String>,
+    pub device: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Address {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "address")?;
+        Ok(Address {
+            address_type: take_attr(&mut el, "type"),
+            domain: take_attr(&mut el, "domain"),
+            bus: take_attr(&mut el, "bus"),
+            slot: take_attr(&mut el, "slot"),
+            function: take_attr(&mut el, "function"),
+            device: take_attr(&mut el, "device"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("address");
+        set_attr(&mut el, "type", &self.address_type);
+        set_attr(&mut el, "domain", &self.domain);
+        set_attr(&mut el, "bus", &self.bus);
+        set_attr(&mut el, "slot", &self.slot);
+        set_attr(&mut el, "function", &self.function);
+        set_attr(&mut el, "device", &self.device);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<source>` inside `<hostdev>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HostdevSource {
+    /// USB vendor ID, such as "0x1234".
+    pub vendor: Option<String>,
+    /// USB product ID.
+    pub product: Option<String>,
+    /// Host address of the device.
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl XmlModel for HostdevSource {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "source")?;
+        Ok(HostdevSource {
+            vendor: take_child_attr(&mut el, "vendor", "id"),
+            product: take_child_attr(&mut el, "product", "id"),
+            address: take_model(&mut el, "address")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        push_child_attr(&mut el, "vendor", "id", &self.vendor);
+        push_child_attr(&mut el, "product", "id", &self.product);
+        push_model(&mut el, &self.address);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A host device assigned to the guest, `<hostdev>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hostdev {
+    /// "subsystem" or "capabilities".
+    pub mode: Option<String>,
+    /// "pci", "usb", "scsi" and so on.
+    pub hostdev_type: Option<String>,
+    pub managed: Option<bool>,
+    pub source: Option<HostdevSource>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Hostdev {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "hostdev")?;
+        Ok(Hostdev {
+            mode: take_attr(&mut el, "mode"),
+            hostdev_type: take_attr(&mut el, "type"),
+            managed: take_attr_bool(&mut el, "managed")?,
+            source: take_model(&mut el, "source")?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("hostdev");
+        set_attr(&mut el, "mode", &self.mode);
+        set_attr(&mut el, "type", &self.hostdev_type);
+        set_attr_bool(&mut el, "managed", self.managed, "yes", "no");
+        push_model(&mut el, &self.source);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A host directory exported to the guest, `<filesystem>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filesystem {
+    /// "mount", "block", "file", "template", "ram" or "bind".
+    pub fs_type: Option<String>,
+    /// "passthrough", "mapped" or "squash".
+    pub accessmode: Option<String>,
+    /// `type` attribute of `<driver>`, such as "path" or "virtiofs".
+    pub driver: Option<String>,
+    /// `dir` attribute of `<source>`.
+    pub source_dir: Option<String>,
+    /// `dir` attribute of `<target>`, or the mount tag.
+    pub target_dir: Option<String>,
+    pub readonly: bool,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Filesystem {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "filesystem")?;
+        Ok(Filesystem {
+            fs_type: take_attr(&mut el, "type"),
+            accessmode: take_attr(&mut el, "accessmode"),
+            driver: take_child_attr(&mut el, "driver", "type"),
+            source_dir: take_child_attr(&mut el, "source", "dir"),
+            target_dir: take_child_attr(&mut el, "target", "dir"),
+            readonly: take_flag(&mut el, "readonly"),
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("filesystem");
+        set_attr(&mut el, "type", &self.fs_type);
+        set_attr(&mut el, "accessmode", &self.accessmode);
+        push_child_attr(&mut el, "driver", "type", &self.driver);
+        push_child_attr(&mut el, "source", "dir", &self.source_dir);
+        push_child_attr(&mut el, "target", "dir", &self.target_dir);
+        push_flag(&mut el, "readonly", self.readonly);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<backend>` inside `<rng>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RngBackend {
+    /// "random", "egd" or "builtin".
+    pub model: Option<String>,
+    /// Host entropy source for the "random" backend.
+    pub source: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for RngBackend {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "backend")?;
+        let model = take_attr(&mut el, "model");
+        let source = take_text(&mut el);
+        Ok(RngBackend {
+            model: model,
+            source: source,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = match self.source {
+            Some(ref s) => text_element("backend", s),
+            None => Element::new("backend"),
+        };
+        set_attr(&mut el, "model", &self.model);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A random number generator device, `<rng>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rng {
+    /// "virtio", "virtio-transitional" or "virtio-non-transitional".
+    pub model: Option<String>,
+    pub backend: Option<RngBackend>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Rng {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "rng")?;
+        Ok(Rng {
+            model: take_attr(&mut el, "model"),
+            backend: take_model(&mut el, "backend")?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("rng");
+        set_attr(&mut el, "model", &self.model);
+        push_model(&mut el, &self.backend);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// `<backend>` inside `<tpm>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TpmBackend {
+    /// "passthrough" or "emulator".
+    pub backend_type: Option<String>,
+    /// TPM version of the emulator, "1.2" or "2.0".
+    pub version: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for TpmBackend {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "backend")?;
+        Ok(TpmBackend {
+            backend_type: take_attr(&mut el, "type"),
+            version: take_attr(&mut el, "version"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("backend");
+        set_attr(&mut el, "type", &self.backend_type);
+        set_attr(&mut el, "version", &self.version);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A TPM device, `<tpm>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tpm {
+    /// "tpm-tis", "tpm-crb" or "spapr-tpm-proxy".
+    pub model: Option<String>,
+    pub backend: Option<TpmBackend>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Tpm {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "tpm")?;
+        Ok(Tpm {
+            model: take_attr(&mut el, "model"),
+            backend: take_model(&mut el, "backend")?,
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("tpm");
+        set_attr(&mut el, "model", &self.model);
+        push_model(&mut el, &self.backend);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A watchdog device, `<watchdog>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watchdog {
+    /// "i6300esb", "ib700", "diag288" or "itco".
+    pub model: Option<String>,
+    /// "reset", "shutdown", "poweroff", "pause", "none", "dump" or
+    /// "inject-nmi".
+    pub action: Option<String>,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Watchdog {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "watchdog")?;
+        Ok(Watchdog {
+            model: take_attr(&mut el, "model"),
+            action: take_attr(&mut el, "action"),
+            alias: take_alias(&mut el),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("watchdog");
+        set_attr(&mut el, "model", &self.model);
+        set_attr(&mut el, "action", &self.action);
+        push_alias(&mut el, &self.alias);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Devices provided to the guest, `<devices>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Devices {
+    /// Path of the device model emulator binary.
+    pub emulator: Option<String>,
+    pub disks: Vec<Disk>,
+    pub controllers: Vec<Controller>,
+    pub filesystems: Vec<Filesystem>,
+    pub interfaces: Vec<Interface>,
+    pub hostdevs: Vec<Hostdev>,
+    pub graphics: Vec<Graphics>,
+    pub serials: Vec<CharDevice>,
+    pub consoles: Vec<CharDevice>,
+    pub channels: Vec<CharDevice>,
+    pub watchdog: Option<Watchdog>,
+    pub rngs: Vec<Rng>,
+    pub tpms: Vec<Tpm>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Devices {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "devices")?;
+        Ok(Devices {
+            emulator: take_child_text(&mut el, "emulator"),
+            disks: take_models(&mut el, "disk")?,
+            controllers: take_models(&mut el, "controller")?,
+            filesystems: take_models(&mut el, "filesystem")?,
+            interfaces: take_models(&mut el, "interface")?,
+            hostdevs: take_models(&mut el, "hostdev")?,
+            graphics: take_models(&mut el, "graphics")?,
+            serials: take_models(&mut el, "serial")?,
+            consoles: take_models(&mut el, "console")?,
+            channels: take_models(&mut el, "channel")?,
+            watchdog: take_model(&mut el, "watchdog")?,
+            rngs: take_models(&mut el, "rng")?,
+            tpms: take_models(&mut el, "tpm")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("devices");
+        push_text(&mut el, "emulator", &self.emulator);
+        push_models(&mut el, &self.disks);
+        push_models(&mut el, &self.controllers);
+        push_models(&mut el, &self.filesystems);
+        push_models(&mut el, &self.interfaces);
+        push_models(&mut el, &self.hostdevs);
+        push_models(&mut el, &self.graphics);
+        push_models(&mut el, &self.serials);
+        push_models(&mut el, &self.consoles);
+        push_models(&mut el, &self.channels);
+        push_model(&mut el, &self.watchdog);
+        push_models(&mut el, &self.rngs);
+        push_models(&mut el, &self.tpms);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A complete domain definition, the root `<domain>` element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainConfig {
+    /// Hypervisor type, such as "kvm", "qemu", "lxc" or "test".
+    pub domain_type: String,
+    /// Hypervisor ID of a running domain.
+    pub id: Option<i32>,
+    pub name: String,
+    pub uuid: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Application specific `<metadata>`, kept as raw XML.
+    pub metadata: Option<Element>,
+    /// Maximum memory allocated at boot.
+    pub memory: Option<Memory>,
+    /// Actual memory allocation, if lower than `memory`.
+    pub current_memory: Option<Memory>,
+    pub vcpu: Option<Vcpu>,
+    pub os: Option<Os>,
+    pub features: Option<Features>,
+    pub cpu: Option<Cpu>,
+    pub clock: Option<Clock>,
+    pub on_poweroff: Option<String>,
+    pub on_reboot: Option<String>,
+    pub on_crash: Option<String>,
+    pub devices: Devices,
+    pub extra: Extra,
+}
+
+impl DomainConfig {
+    /// A minimal definition with the given hypervisor type and name.
+    pub fn new(domain_type: &str, name: &str) -> DomainConfig {
+        DomainConfig {
+            domain_type: domain_type.to_string(),
+            id: None,
+            name: name.to_string(),
+            uuid: None,
+            title: None,
+            description: None,
+            metadata: None,
+            memory: None,
+            current_memory: None,
+            vcpu: None,
+            os: None,
+            features: None,
+            cpu: None,
+            clock: None,
+            on_poweroff: None,
+            on_reboot: None,
+            on_crash: None,
+            devices: Devices::default(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl XmlModel for DomainConfig {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "domain")?;
+        let domain_type = match take_attr(&mut el, "type") {
+            Some(t) => t,
+            None => return Err(xml_error("missing 'type' attribute in <domain>")),
+        };
+        let name = match take_child_text(&mut el, "name") {
+            Some(n) => n,
+            None => return Err(xml_error("missing <name> in <domain>")),
+        };
+        Ok(DomainConfig {
+            domain_type: domain_type,
+            id: take_attr_parse(&mut el, "id")?,
+            name: name,
+            uuid: take_child_text(&mut el, "uuid"),
+            title: take_child_text(&mut el, "title"),
+            description: take_child_text(&mut el, "description"),
+            metadata: take_child(&mut el, "metadata"),
+            memory: take_memory(&mut el, "memory")?,
+            current_memory: take_memory(&mut el, "currentMemory")?,
+            vcpu: take_model(&mut el, "vcpu")?,
+            os: take_model(&mut el, "os")?,
+            features: take_model(&mut el, "features")?,
+            cpu: take_model(&mut el, "cpu")?,
+            clock: take_model(&mut el, "clock")?,
+            on_poweroff: take_child_text(&mut el, "on_poweroff"),
+            on_reboot: take_child_text(&mut el, "on_reboot"),
+            on_crash: take_child_text(&mut el, "on_crash"),
+            devices: take_model(&mut el, "devices")?.unwrap_or_default(),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("domain");
+        set_attr(&mut el, "type", &Some(&self.domain_type));
+        set_attr(&mut el, "id", &self.id);
+        push_text(&mut el, "name", &Some(&self.name));
+        push_text(&mut el, "uuid", &self.uuid);
+        push_text(&mut el, "title", &self.title);
+        push_text(&mut el, "description", &self.description);
+        if let Some(ref m) = self.metadata {
+            push(&mut el, m.clone());
+        }
+        push_memory(&mut el, "memory", &self.memory);
+        push_memory(&mut el, "currentMemory", &self.current_memory);
+        push_model(&mut el, &self.vcpu);
+        push_model(&mut el, &self.os);
+        push_model(&mut el, &self.features);
+        push_model(&mut el, &self.cpu);
+        push_model(&mut el, &self.clock);
+        push_text(&mut el, "on_poweroff", &self.on_poweroff);
+        push_text(&mut el, "on_reboot", &self.on_reboot);
+        push_text(&mut el, "on_crash", &self.on_crash);
+        // An empty <devices/> is only written back if it was there.
+        if self.devices != Devices::default() || self.extra.had_child("devices") {
+            push(&mut el, self.devices.to_element());
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+impl Domain {
+    /// Fetch the XML description of the domain and parse it into a
+    /// `DomainConfig`.
+    pub fn get_config(&self, flags: XmlFlags) -> Result<DomainConfig, Error> {
+        DomainConfig::from_xml(&self.get_xml_desc(flags)?)
+    }
+
+    /// Define a domain from a typed configuration, see `define_xml()`.
+    pub fn define(conn: &Connect, config: &DomainConfig) -> Result<Domain, Error> {
+        Domain::define_xml(conn, &config.to_xml())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `virsh dumpxml` of a running KVM guest, with an unknown
+    /// element and attribute added to a disk.
+    const KVM_GUEST: &'static str = r#"<domain type='kvm' id='3'>
+  <name>fedora</name>
+  <uuid>7b5a7b5f-6a4c-4a3a-9d4e-3f1a3c2b1a00</uuid>
+  <metadata>
+    <libosinfo:libosinfo xmlns:libosinfo="http://libosinfo.org/xmlns/libvirt/domain/1.0">
+      <libosinfo:os id="http://fedoraproject.org/fedora/38"/>
+    </libosinfo:libosinfo>
+  </metadata>
+  <memory unit='KiB'>2097152</memory>
+  <currentMemory unit='KiB'>2097152</currentMemory>
+  <vcpu placement='static'>2</vcpu>
+  <resource>
+    <partition>/machine</partition>
+  </resource>
+  <os>
+    <type arch='x86_64' machine='pc-q35-7.2'>hvm</type>
+    <boot dev='hd'/>
+  </os>
+  <features>
+    <acpi/>
+    <apic/>
+    <vmport state='off'/>
+  </features>
+  <cpu mode='host-passthrough' check='none' migratable='on'>
+    <topology sockets='1' dies='1' cores='2' threads='1'/>
+  </cpu>
+  <clock offset='utc'>
+    <timer name='rtc' tickpolicy='catchup'/>
+    <timer name='pit' tickpolicy='delay'/>
+    <timer name='hpet' present='no'/>
+  </clock>
+  <on_poweroff>destroy</on_poweroff>
+  <on_reboot>restart</on_reboot>
+  <on_crash>destroy</on_crash>
+  <pm>
+    <suspend-to-mem enabled='no'/>
+    <suspend-to-disk enabled='no'/>
+  </pm>
+  <devices>
+    <emulator>/usr/bin/qemu-system-x86_64</emulator>
+    <disk type='file' device='disk' model='virtio-non-transitional'>
+      <driver name='qemu' type='qcow2' discard='unmap'/>
+      <source file='/var/lib/libvirt/images/fedora.qcow2' index='2'/>
+      <backingStore type='file' index='3'>
+        <format type='qcow2'/>
+        <source file='/var/lib/libvirt/images/base.qcow2'/>
+        <backingStore/>
+      </backingStore>
+      <target dev='vda' bus='virtio'/>
+      <iotune>
+        <total_bytes_sec>10485760</total_bytes_sec>
+      </iotune>
+      <alias name='virtio-disk0'/>
+      <address type='pci' domain='0x0000' bus='0x04' slot='0x00' function='0x0'/>
+    </disk>
+    <disk type='file' device='cdrom'>
+      <driver name='qemu'/>
+      <target dev='sda' bus='sata'/>
+      <readonly/>
+      <alias name='sata0-0-0'/>
+      <address type='drive' controller='0' bus='0' target='0' unit='0'/>
+    </disk>
+    <controller type='usb' index='0' model='qemu-xhci' ports='15'>
+      <alias name='usb'/>
+      <address type='pci' domain='0x0000' bus='0x02' slot='0x00' function='0x0'/>
+    </controller>
+    <controller type='pci' index='0' model='pcie-root'>
+      <alias name='pcie.0'/>
+    </controller>
+    <interface type='network'>
+      <mac address='52:54:00:6b:3c:58'/>
+      <source network='default' portid='2c3e8d3a-7b6f-4c25-9f36-2a5f6b1b8e4d' bridge='virbr0'/>
+      <target dev='vnet2'/>
+      <model type='virtio'/>
+      <alias name='net0'/>
+      <address type='pci' domain='0x0000' bus='0x01' slot='0x00' function='0x0'/>
+    </interface>
+    <serial type='pty'>
+      <source path='/dev/pts/3'/>
+      <target type='isa-serial' port='0'>
+        <model name='isa-serial'/>
+      </target>
+      <alias name='serial0'/>
+    </serial>
+    <console type='pty' tty='/dev/pts/3'>
+      <source path='/dev/pts/3'/>
+      <target type='serial' port='0'/>
+      <alias name='serial0'/>
+    </console>
+    <channel type='unix'>
+      <source mode='bind' path='/run/libvirt/qemu/channel/3-fedora/org.qemu.guest_agent.0'/>
+      <target type='virtio' name='org.qemu.guest_agent.0' state='connected'/>
+      <alias name='channel0'/>
+      <address type='virtio-serial' controller='0' bus='0' port='1'/>
+    </channel>
+    <input type='tablet' bus='usb'>
+      <alias name='input0'/>
+      <address type='usb' bus='0' port='1'/>
+    </input>
+    <graphics type='vnc' port='5900' autoport='yes' listen='127.0.0.1'>
+      <listen type='address' address='127.0.0.1'/>
+    </graphics>
+    <video>
+      <model type='virtio' heads='1' primary='yes'/>
+      <alias name='video0'/>
+    </video>
+    <watchdog model='itco' action='reset'>
+      <alias name='watchdog0'/>
+    </watchdog>
+    <memballoon model='virtio'>
+      <alias name='balloon0'/>
+    </memballoon>
+    <rng model='virtio'>
+      <backend model='random'>/dev/urandom</backend>
+      <alias name='rng0'/>
+    </rng>
+  </devices>
+  <seclabel type='dynamic' model='selinux' relabel='yes'>
+    <label>system_u:system_r:svirt_t:s0:c52,c718</label>
+    <imagelabel>system_u:object_r:svirt_image_t:s0:c52,c718</imagelabel>
+  </seclabel>
+</domain>"#;
+
+    /// `virsh -c lxc:/// dumpxml` of a container.
+    const LXC_CONTAINER: &'static str = r#"<domain type='lxc'>
+  <name>container</name>
+  <uuid>1c3b1d5e-2f6a-4d8b-9a0c-5e7f9b1d3a2c</uuid>
+  <memory unit='KiB'>524288</memory>
+  <currentMemory unit='KiB'>524288</currentMemory>
+  <vcpu placement='static'>1</vcpu>
+  <resource>
+    <partition>/machine</partition>
+  </resource>
+  <os>
+    <type arch='x86_64'>exe</type>
+    <init>/sbin/init</init>
+  </os>
+  <idmap>
+    <uid start='0' target='100000' count='65536'/>
+    <gid start='0' target='100000' count='65536'/>
+  </idmap>
+  <features>
+    <privnet/>
+  </features>
+  <clock offset='utc'/>
+  <on_poweroff>destroy</on_poweroff>
+  <on_reboot>restart</on_reboot>
+  <on_crash>destroy</on_crash>
+  <devices>
+    <emulator>/usr/libexec/libvirt_lxc</emulator>
+    <filesystem type='mount' accessmode='passthrough'>
+      <source dir='/var/lib/lxc/container/rootfs'/>
+      <target dir='/'/>
+    </filesystem>
+    <interface type='bridge'>
+      <mac address='00:16:3e:5d:c7:9e'/>
+      <source bridge='br0'/>
+    </interface>
+    <console type='pty'>
+      <target type='lxc' port='0'/>
+    </console>
+  </devices>
+</domain>"#;
+
+    /// Check that `xml` survives a parse/serialize round trip.
+    fn assert_round_trip(xml: &str) -> DomainConfig {
+        let config = DomainConfig::from_xml(xml).unwrap();
+        let written = config.to_xml();
+        assert_eq!(parse(&written).unwrap(), parse(xml).unwrap(), "round trip changed the XML:\n{}", written);
+        assert_eq!(DomainConfig::from_xml(&written).unwrap(), config);
+        config
+    }
+
+    #[test]
+    fn kvm_guest_round_trip() {
+        let config = assert_round_trip(KVM_GUEST);
+        assert_eq!(config.id, Some(3));
+        assert_eq!(config.memory.as_ref().unwrap().to_kib().unwrap(), 2097152);
+        let topology = config.cpu.as_ref().unwrap().topology.as_ref().unwrap();
+        assert_eq!((topology.sockets, topology.dies, topology.cores, topology.threads),
+                   (Some(1), Some(1), Some(2), Some(1)));
+        let disk = &config.devices.disks[0];
+        assert_eq!(disk.target.as_ref().unwrap().dev.as_ref().unwrap(), "vda");
+        assert_eq!(disk.extra.attributes, vec![("model".to_string(), "virtio-non-transitional".to_string())]);
+        assert_eq!(disk.extra.children.iter().map(|c| &c.name[..]).collect::<Vec<_>>(), vec!["iotune", "address"]);
+        assert!(config.devices.disks[1].readonly);
+        assert_eq!(config.devices.rngs[0].backend.as_ref().unwrap().source.as_ref().unwrap(), "/dev/urandom");
+        assert_eq!(config.devices.extra.children.iter().map(|c| &c.name[..]).collect::<Vec<_>>(),
+                   vec!["input", "video", "memballoon"]);
+        assert_eq!(config.extra.children.iter().map(|c| &c.name[..]).collect::<Vec<_>>(),
+                   vec!["resource", "pm", "seclabel"]);
+    }
+
+    #[test]
+    fn lxc_container_round_trip() {
+        let config = assert_round_trip(LXC_CONTAINER);
+        assert_eq!(config.os.as_ref().unwrap().init.as_ref().unwrap(), "/sbin/init");
+        assert!(config.features.as_ref().unwrap().privnet);
+        assert_eq!(config.devices.filesystems[0].source_dir.as_ref().unwrap(), "/var/lib/lxc/container/rootfs");
+        assert_eq!(config.devices.consoles[0].device_type.as_ref().unwrap(), "pty");
+    }
+
+    #[test]
+    fn namespaced_metadata() {
+        let config = DomainConfig::from_xml(KVM_GUEST).unwrap();
+        let metadata = config.metadata.as_ref().unwrap();
+        let os = metadata.get_child("libosinfo").unwrap();
+        assert_eq!(os.prefix.as_ref().unwrap(), "libosinfo");
+        assert_eq!(os.namespace.as_ref().unwrap(), "http://libosinfo.org/xmlns/libvirt/domain/1.0");
+        let written = DomainConfig::from_xml(&config.to_xml()).unwrap();
+        assert_eq!(written.metadata, config.metadata);
+    }
+
+    #[test]
+    fn unknown_children_keep_their_place() {
+        let config = DomainConfig::from_xml(KVM_GUEST).unwrap();
+        let disk = config.devices.disks[0].to_element();
+        let names: Vec<_> = disk.children.iter().filter_map(|n| n.as_element()).map(|c| &c.name[..]).collect();
+        assert_eq!(names, vec!["driver", "source", "backingStore", "target", "iotune", "alias", "address"]);
+    }
+
+    #[test]
+    fn missing_values_stay_missing() {
+        let xml = "<domain type='qemu'>
+  <name>minimal</name>
+  <cpu>
+    <topology sockets='2'/>
+  </cpu>
+  <devices>
+    <disk>
+      <target bus='virtio'/>
+    </disk>
+    <interface/>
+    <controller/>
+    <graphics/>
+    <serial/>
+    <rng/>
+  </devices>
+</domain>";
+        let config = assert_round_trip(xml);
+        let topology = config.cpu.as_ref().unwrap().topology.as_ref().unwrap();
+        assert_eq!((topology.sockets, topology.cores, topology.threads), (Some(2), None, None));
+        let devices = &config.devices;
+        assert_eq!(devices.disks[0].disk_type, None);
+        assert_eq!(devices.disks[0].target.as_ref().unwrap().dev, None);
+        assert_eq!(devices.interfaces[0].interface_type, None);
+        assert_eq!(devices.controllers[0].controller_type, None);
+        assert_eq!(devices.graphics[0].graphics_type, None);
+        assert_eq!(devices.serials[0].device_type, None);
+        assert_eq!(devices.rngs[0].model, None);
+    }
+
+    #[test]
+    fn devices_only_written_when_present() {
+        let config = assert_round_trip("<domain type='test'><name>empty</name></domain>");
+        assert!(config.to_element().get_child("devices").is_none());
+        assert_round_trip("<domain type='test'><name>empty</name><devices/></domain>");
+    }
+
+    #[test]
+    fn text_is_not_trimmed() {
+        let config = assert_round_trip("<domain type='test'><name>t</name><description> two\n lines </description></domain>");
+        assert_eq!(config.description.as_ref().unwrap(), " two\n lines ");
+    }
+
+    #[test]
+    fn memory_overflow() {
+        let memory = Memory::new(u64::max_value() / 1024 + 1, MemoryUnit::KiB);
+        assert!(memory.to_bytes(MemoryUnit::KiB).is_err());
+        assert!(memory.to_kib().is_err());
+        assert_eq!(Memory::new(2, MemoryUnit::GiB).to_kib().unwrap(), 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn disk_backing_chain() {
+        let disk = Disk::from_xml("<disk type='file' device='disk'>
+  <driver name='qemu' type='qcow2'/>
+  <source file='/var/lib/libvirt/images/snap2.qcow2' index='3'/>
+  <backingStore type='file' index='2'>
+    <format type='qcow2'/>
+    <source file='/var/lib/libvirt/images/snap1.qcow2'/>
+    <backingStore type='network' index='1'>
+      <format type='raw'/>
+      <source protocol='rbd' name='images/base'>
+        <host name='mon1.example.com' port='6789'/>
+      </source>
+      <backingStore/>
+    </backingStore>
+  </backingStore>
+  <target dev='vda' bus='virtio'/>
+</disk>").unwrap();
+        assert_eq!(disk.backing_chain(),
+                   vec![BackingImage {
+                            path: Some("/var/lib/libvirt/images/snap1.qcow2".to_string()),
+                            format: Some("qcow2".to_string()),
+                        },
+                        BackingImage {
+                            path: Some("images/base".to_string()),
+                            format: Some("raw".to_string()),
+                        }]);
+        let store = disk.backing_store.as_ref().unwrap();
+        assert_eq!(store.index, Some(2));
+        assert!(store.backing_store.as_ref().unwrap().backing_store.as_ref().unwrap().is_end());
+    }
+
+    #[test]
+    fn disk_without_backing_chain() {
+        let disk = Disk::from_xml("<disk type='block' device='disk'>
+  <source dev='/dev/vg0/guest'/>
+  <target dev='vda' bus='virtio'/>
+</disk>").unwrap();
+        assert!(disk.backing_chain().is_empty());
+    }
+
+    #[test]
+    fn root_namespaces_stay_on_the_root() {
+        let xml = "<domain type='kvm' xmlns:qemu='http://libvirt.org/schemas/domain/qemu/1.0'>
+  <name>debug</name>
+  <memory unit='KiB'>1048576</memory>
+  <os>
+    <type arch='x86_64' machine='pc-q35-7.2'>hvm</type>
+    <nvram>/var/lib/libvirt/qemu/nvram/debug_VARS.fd</nvram>
+  </os>
+  <devices>
+    <interface type='network'>
+      <mac address='52:54:00:12:34:56'/>
+      <source network='default'/>
+      <driver name='vhost' queues='2'/>
+    </interface>
+    <graphics type='spice' autoport='yes'>
+      <listen type='address'/>
+      <image compression='off'/>
+    </graphics>
+  </devices>
+  <seclabel type='dynamic' model='selinux'/>
+  <qemu:commandline>
+    <qemu:arg value='-s'/>
+  </qemu:commandline>
+</domain>";
+        let config = assert_round_trip(xml);
+        let written = config.to_xml();
+        assert_eq!(written.matches("xmlns:qemu").count(), 1, "{}", written);
+        assert!(written.lines().next().unwrap().contains("xmlns:qemu"), "{}", written);
+        let commandline = config.extra.children.iter().find(|c| c.name == "commandline").unwrap();
+        assert_eq!(commandline.prefix.as_ref().unwrap(), "qemu");
+        assert_eq!(commandline.namespaces, None);
+    }
+}
@@ -4,9 +4,9 @@ extern crate libvirt_sys as sys;
 use std::error::Error as StdError;
 use std::fmt::{Display, Result as FmtResult, Formatter};
 use std::os::raw::c_void;
-use std::ptr::NonNull;
+use std::sync::Mutex;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
 pub enum ErrorLevel {
     NONE = 0,
@@ -17,44 +17,378 @@ pub enum ErrorLevel {
 }
 impl_from! { u32, ErrorLevel }
 
+/// Declares an enum mirroring a C enum, with an `Other` variant for
+/// values added by newer libvirt releases.
+macro_rules! c_enum {
+    ($(#[$meta:meta])* pub enum $name:ident { $($variant:ident = $value:expr,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($variant,)*
+            /// A value unknown to this crate.
+            Other(i32),
+        }
+
+        impl $name {
+            pub fn from_int(n: i32) -> $name {
+                match n {
+                    $($value => $name::$variant,)*
+                    n => $name::Other(n),
+                }
+            }
+
+            pub fn to_int(self) -> i32 {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(n) => n,
+                }
+            }
+        }
+    }
+}
+
+c_enum! {
+    /// What went wrong, see virErrorNumber.
+    pub enum ErrorNumber {
+        Ok = 0,
+        InternalError = 1,
+        NoMemory = 2,
+        NoSupport = 3,
+        UnknownHost = 4,
+        NoConnect = 5,
+        InvalidConn = 6,
+        InvalidDomain = 7,
+        InvalidArg = 8,
+        OperationFailed = 9,
+        GetFailed = 10,
+        PostFailed = 11,
+        HttpError = 12,
+        SexprSerial = 13,
+        NoXen = 14,
+        XenCall = 15,
+        OsType = 16,
+        NoKernel = 17,
+        NoRoot = 18,
+        NoSource = 19,
+        NoTarget = 20,
+        NoName = 21,
+        NoOs = 22,
+        NoDevice = 23,
+        NoXenstore = 24,
+        DriverFull = 25,
+        CallFailed = 26,
+        XmlError = 27,
+        DomExist = 28,
+        OperationDenied = 29,
+        OpenFailed = 30,
+        ReadFailed = 31,
+        ParseFailed = 32,
+        ConfSyntax = 33,
+        WriteFailed = 34,
+        XmlDetail = 35,
+        InvalidNetwork = 36,
+        NetworkExist = 37,
+        SystemError = 38,
+        Rpc = 39,
+        GnutlsError = 40,
+        WarNoNetwork = 41,
+        NoDomain = 42,
+        NoNetwork = 43,
+        InvalidMac = 44,
+        AuthFailed = 45,
+        InvalidStoragePool = 46,
+        InvalidStorageVol = 47,
+        WarNoStorage = 48,
+        NoStoragePool = 49,
+        NoStorageVol = 50,
+        WarNoNode = 51,
+        InvalidNodeDevice = 52,
+        NoNodeDevice = 53,
+        NoSecurityModel = 54,
+        OperationInvalid = 55,
+        WarNoInterface = 56,
+        NoInterface = 57,
+        InvalidInterface = 58,
+        MultipleInterfaces = 59,
+        WarNoNwfilter = 60,
+        InvalidNwfilter = 61,
+        NoNwfilter = 62,
+        BuildFirewall = 63,
+        WarNoSecret = 64,
+        InvalidSecret = 65,
+        NoSecret = 66,
+        ConfigUnsupported = 67,
+        OperationTimeout = 68,
+        MigratePersistFailed = 69,
+        HookScriptFailed = 70,
+        InvalidDomainSnapshot = 71,
+        NoDomainSnapshot = 72,
+        InvalidStream = 73,
+        ArgumentUnsupported = 74,
+        StorageProbeFailed = 75,
+        StoragePoolBuilt = 76,
+        SnapshotRevertRisky = 77,
+        OperationAborted = 78,
+        AuthCancelled = 79,
+        NoDomainMetadata = 80,
+        MigrateUnsafe = 81,
+        Overflow = 82,
+        BlockCopyActive = 83,
+        OperationUnsupported = 84,
+        Ssh = 85,
+        AgentUnresponsive = 86,
+        ResourceBusy = 87,
+        AccessDenied = 88,
+        DbusService = 89,
+        StorageVolExist = 90,
+        CpuIncompatible = 91,
+        XmlInvalidSchema = 92,
+        MigrateFinishOk = 93,
+        AuthUnavailable = 94,
+        NoServer = 95,
+        NoClient = 96,
+        AgentUnsynced = 97,
+        Libssh = 98,
+        DeviceMissing = 99,
+        InvalidNwfilterBinding = 100,
+        NoNwfilterBinding = 101,
+        InvalidDomainCheckpoint = 102,
+        NoDomainCheckpoint = 103,
+        NoDomainBackup = 104,
+        InvalidNetworkPort = 105,
+        NetworkPortExist = 106,
+        NoNetworkPort = 107,
+        NoHostname = 108,
+        CheckpointInconsistent = 109,
+        MultipleDomains = 110,
+    }
+}
+
+c_enum! {
+    /// The part of libvirt an error comes from, see virErrorDomain.
+    pub enum ErrorDomain {
+        None = 0,
+        Xen = 1,
+        Xend = 2,
+        Xenstore = 3,
+        Sexpr = 4,
+        Xml = 5,
+        Dom = 6,
+        Rpc = 7,
+        Proxy = 8,
+        Conf = 9,
+        Qemu = 10,
+        Net = 11,
+        Test = 12,
+        Remote = 13,
+        Openvz = 14,
+        Xenxm = 15,
+        StatsLinux = 16,
+        Lxc = 17,
+        Storage = 18,
+        Network = 19,
+        Domain = 20,
+        Uml = 21,
+        Nodedev = 22,
+        XenInotify = 23,
+        Security = 24,
+        Vbox = 25,
+        Interface = 26,
+        One = 27,
+        Esx = 28,
+        Phyp = 29,
+        Secret = 30,
+        Cpu = 31,
+        Xenapi = 32,
+        Nwfilter = 33,
+        Hook = 34,
+        DomainSnapshot = 35,
+        Audit = 36,
+        Sysinfo = 37,
+        Streams = 38,
+        Vmware = 39,
+        Event = 40,
+        Libxl = 41,
+        Locking = 42,
+        Hyperv = 43,
+        Capabilities = 44,
+        Uri = 45,
+        Auth = 46,
+        Dbus = 47,
+        Parallels = 48,
+        Device = 49,
+        Ssh = 50,
+        Lockspace = 51,
+        Initctl = 52,
+        Identity = 53,
+        Cgroup = 54,
+        Access = 55,
+        Systemd = 56,
+        Bhyve = 57,
+        Crypto = 58,
+        Firewall = 59,
+        Polkit = 60,
+        Thread = 61,
+        Admin = 62,
+        Logging = 63,
+        Xenxl = 64,
+        Perf = 65,
+        Libssh = 66,
+        Resctrl = 67,
+        Firewalld = 68,
+        DomainCheckpoint = 69,
+        Tpm = 70,
+        Bpf = 71,
+    }
+}
+
 /// Error handling
 ///
 /// See: http://libvirt.org/html/libvirt-virterror.html
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Error {
+    /// Raw virErrorNumber, see `number()`.
     pub code: i32,
+    /// Raw virErrorDomain, see `error_domain()`.
     pub domain: i32,
     pub message: String,
     pub level: ErrorLevel,
+    /// Extra information, whose meaning depends on `code`.
+    pub str1: Option<String>,
+    pub str2: Option<String>,
+    pub str3: Option<String>,
+    pub int1: i32,
+    pub int2: i32,
 }
 
 impl Error {
-    /// Returns the most-recent libvirt error
+    /// An error raised by this crate rather than by libvirt.
+    pub fn new<S: Into<String>>(number: ErrorNumber, domain: ErrorDomain, message: S) -> Error {
+        Error {
+            code: number.to_int(),
+            domain: domain.to_int(),
+            message: message.into(),
+            level: ErrorLevel::ERROR,
+            str1: None,
+            str2: None,
+            str3: None,
+            int1: 0,
+            int2: 0,
+        }
+    }
+
+    /// Returns the most-recent libvirt error raised on the calling
+    /// thread, and clears it.
+    ///
+    /// libvirt keeps errors per thread, so this must be called on the
+    /// thread which made the failing call, before it makes another one.
     pub fn last_error() -> Error {
         unsafe {
             let ptr: sys::virErrorPtr = sys::virGetLastError();
-            Error::from_ptr(ptr)
+            if ptr.is_null() {
+                return Error::new(ErrorNumber::InternalError,
+                                  ErrorDomain::None,
+                                  "an error occurred, but the cause is unknown");
+            }
+            let err = Error::from_ptr(ptr);
+            sys::virResetLastError();
+            err
+        }
+    }
+
+    /// Clear the error of the calling thread.
+    pub fn reset_last_error() {
+        unsafe {
+            sys::virResetLastError();
         }
     }
 
     pub fn from_ptr(ptr: sys::virErrorPtr) -> Error {
+        unsafe fn opt_string(s: *mut libc::c_char) -> Option<String> {
+            if s.is_null() {
+                None
+            } else {
+                Some(c_chars_to_string!(s, nofree))
+            }
+        }
+
         unsafe {
             Error {
                 code: (*ptr).code,
                 domain: (*ptr).domain,
-                message: c_chars_to_string!((*ptr).message, nofree),
+                message: opt_string((*ptr).message).unwrap_or_default(),
                 level: ErrorLevel::from((*ptr).level),
+                str1: opt_string((*ptr).str1),
+                str2: opt_string((*ptr).str2),
+                str3: opt_string((*ptr).str3),
+                int1: (*ptr).int1 as i32,
+                int2: (*ptr).int2 as i32,
             }
         }
     }
-}
 
-impl StdError for Error {
-    fn description(&self) -> &str {
-        self.message.as_str()
+    pub fn number(&self) -> ErrorNumber {
+        ErrorNumber::from_int(self.code)
+    }
+
+    pub fn error_domain(&self) -> ErrorDomain {
+        ErrorDomain::from_int(self.domain)
+    }
+
+    /// The domain looked up doesn't exist.
+    pub fn is_no_domain(&self) -> bool {
+        self.number() == ErrorNumber::NoDomain
+    }
+
+    /// The storage pool looked up doesn't exist.
+    pub fn is_no_storage_pool(&self) -> bool {
+        self.number() == ErrorNumber::NoStoragePool
+    }
+
+    /// The storage volume looked up doesn't exist.
+    pub fn is_no_storage_vol(&self) -> bool {
+        self.number() == ErrorNumber::NoStorageVol
+    }
+
+    /// The secret looked up doesn't exist.
+    pub fn is_no_secret(&self) -> bool {
+        self.number() == ErrorNumber::NoSecret
+    }
+
+    /// The operation isn't valid in the current state of the object,
+    /// such as starting a domain which is already running.
+    pub fn is_operation_invalid(&self) -> bool {
+        self.number() == ErrorNumber::OperationInvalid
+    }
+
+    /// The driver, hypervisor or configuration doesn't support what
+    /// was asked.
+    pub fn is_unsupported(&self) -> bool {
+        match self.number() {
+            ErrorNumber::NoSupport |
+            ErrorNumber::ConfigUnsupported |
+            ErrorNumber::ArgumentUnsupported |
+            ErrorNumber::OperationUnsupported => true,
+            _ => false,
+        }
+    }
+
+    /// The operation failed for a transient reason, such as a busy
+    /// resource, a timeout or a guest agent that didn't answer, and
+    /// may succeed if tried again later.
+    pub fn is_retryable(&self) -> bool {
+        match self.number() {
+            ErrorNumber::OperationTimeout |
+            ErrorNumber::ResourceBusy |
+            ErrorNumber::AgentUnresponsive |
+            ErrorNumber::AgentUnsynced => true,
+            _ => false,
+        }
     }
 }
 
+impl StdError for Error {}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         write!(f,
@@ -66,18 +400,39 @@ impl Display for Error {
     }
 }
 
-static mut HANDLER: Option<NonNull<Fn(Error)>> = None;
+/// The handler installed by `set_error_func()`, kept alive for as long
+/// as libvirt may call it.
+static HANDLER: Mutex<Option<Box<dyn Fn(Error) + Send + Sync>>> = Mutex::new(None);
 
-pub fn set_error_func<F: Fn(Error) + 'static>(f: F) {
+/// Install `f` as the handler for every error libvirt reports,
+/// replacing any previous one.
+///
+/// libvirt calls the handler on whichever thread hit the error, so it
+/// must be `Send` and `Sync`. A handler holding an `Rc` is rejected:
+///
+/// ```compile_fail
+/// # use new_libvirt::error::set_error_func;
+/// # use std::rc::Rc;
+/// let errors = Rc::new(());
+/// set_error_func(move |_| {
+///     let _ = &errors;
+/// });
+/// ```
+pub fn set_error_func<F>(f: F)
+    where F: Fn(Error) + Send + Sync + 'static
+{
     unsafe extern "C" fn callback<F: Fn(Error)>(user_data: *mut c_void, error: sys::virErrorPtr) {
         let f = user_data as *const F;
         let wrapped_error = Error::from_ptr(error);
         (*f)(wrapped_error)
     }
-    let data = Box::into_raw(Box::new(f));
+    let f = Box::new(f);
+    let data = &*f as *const F as *mut c_void;
+    // The lock keeps concurrent calls from both dropping the same old
+    // handler, which is only dropped once libvirt points at the new one.
+    let mut handler = HANDLER.lock().unwrap_or_else(|e| e.into_inner());
     unsafe {
-        HANDLER.take().map(|p| Box::from_raw(p.as_ptr())); //drop the old one
-        HANDLER = Some(NonNull::new_unchecked(data));
-        sys::virSetErrorFunc(data as _, Some(callback::<F>))
+        sys::virSetErrorFunc(data, Some(callback::<F>));
     }
+    *handler = Some(f);
 }
@@ -0,0 +1,107 @@
+extern crate libc;
+extern crate libvirt_sys as sys;
+
+use error::Error;
+
+/// Register the default event loop implementation, based on poll().
+///
+/// This must be called before opening a connection on which event
+/// callbacks will be registered.
+pub fn event_register_default_impl() -> Result<(), Error> {
+    unsafe {
+        if sys::virEventRegisterDefaultImpl() == -1 {
+            return Err(Error::last_error());
+        }
+        return Ok(());
+    }
+}
+
+/// Run one iteration of the default event loop, dispatching any
+/// pending event callbacks. Applications usually call this in a loop
+/// on a dedicated thread.
+pub fn event_run_default_impl() -> Result<(), Error> {
+    unsafe {
+        if sys::virEventRunDefaultImpl() == -1 {
+            return Err(Error::last_error());
+        }
+        return Ok(());
+    }
+}
+
+/// Keeps an event callback registered on a connection, and
+/// deregisters it when dropped.
+///
+/// The registration holds its own reference to the connection, so it
+/// may outlive the `Connect` it was created from.
+///
+/// Event callbacks must be `Send`: libvirt calls them, and drops them
+/// once deregistered, on whichever thread runs the event loop, which
+/// usually isn't the thread that registered them. A callback holding
+/// an `Rc` is rejected:
+///
+/// ```compile_fail
+/// # use new_libvirt::connect::Connect;
+/// # use std::rc::Rc;
+/// let conn = Connect::open("test:///default").unwrap();
+/// let events = Rc::new(());
+/// let registration = conn.domain_event_lifecycle_register(None, move |_, _, _| {
+///     let _ = &events;
+/// });
+/// ```
+#[derive(Debug)]
+pub struct EventRegistration {
+    conn: sys::virConnectPtr,
+    callback_id: i32,
+    deregister: unsafe extern "C" fn(sys::virConnectPtr, libc::c_int) -> libc::c_int,
+}
+
+impl EventRegistration {
+    /// Wrap the callback `callback_id` registered on `conn`. Takes a
+    /// new reference to `conn`.
+    pub unsafe fn new(conn: sys::virConnectPtr,
+                      callback_id: i32,
+                      deregister: unsafe extern "C" fn(sys::virConnectPtr, libc::c_int) -> libc::c_int)
+                      -> EventRegistration {
+        sys::virConnectRef(conn);
+        EventRegistration {
+            conn: conn,
+            callback_id: callback_id,
+            deregister: deregister,
+        }
+    }
+
+    pub fn callback_id(&self) -> i32 {
+        self.callback_id
+    }
+
+    /// Deregister the callback now, reporting any error. Dropping the
+    /// registration does the same but ignores errors.
+    pub fn deregister(mut self) -> Result<(), Error> {
+        self.release()
+    }
+
+    fn release(&mut self) -> Result<(), Error> {
+        if self.conn.is_null() {
+            return Ok(());
+        }
+        unsafe {
+            let ret = (self.deregister)(self.conn, self.callback_id as libc::c_int);
+            let err = if ret == -1 { Some(Error::last_error()) } else { None };
+            sys::virConnectClose(self.conn);
+            self.conn = ::std::ptr::null_mut();
+            match err {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+impl Drop for EventRegistration {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+unsafe impl Send for EventRegistration {}
+unsafe impl Sync for EventRegistration {}
@@ -0,0 +1,48 @@
+//! Handles borrowed from other libvirt objects.
+//!
+//! Most handles own a reference to the libvirt object they point to
+//! and release it when dropped; cloning one takes a new reference.
+//! Some pointers are only lent out by libvirt, such as the connection
+//! returned by `Domain::get_connect()` or the objects passed to event
+//! callbacks. Those are wrapped in a `Borrowed` handle, which can't
+//! outlive the object it was borrowed from and doesn't release
+//! anything when dropped.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+
+/// A `T` borrowed for the lifetime `'a`. It derefs to `T`; clone the
+/// `T` to get a handle which owns its own reference.
+pub struct Borrowed<'a, T> {
+    handle: ManuallyDrop<T>,
+    _owner: PhantomData<&'a ()>,
+}
+
+impl<'a, T> Borrowed<'a, T> {
+    /// Wrap `handle` without taking ownership of its reference.
+    ///
+    /// The caller must make sure the object stays alive for `'a`, and
+    /// that nothing else releases it through `handle`.
+    pub unsafe fn new(handle: T) -> Borrowed<'a, T> {
+        Borrowed {
+            handle: ManuallyDrop::new(handle),
+            _owner: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Deref for Borrowed<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.handle
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Borrowed<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Borrowed").field(&*self.handle).finish()
+    }
+}
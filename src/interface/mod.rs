@@ -3,6 +3,7 @@ extern crate libvirt_sys as sys;
 use error::Error;
 
 use connect::Connect;
+use handle::Borrowed;
 // use std::ffi::CStr;
 // use std::{ptr, slice, mem};
 // use std::os::raw::c_int;
@@ -26,6 +27,9 @@ impl Drop for Interface {
     }
 }
 
+unsafe impl Send for Interface {}
+unsafe impl Sync for Interface {}
+
 impl Interface {
     pub fn new(ptr: sys::virInterfacePtr) -> Interface {
         return Interface { ptr: Some(ptr) };
@@ -35,13 +39,16 @@ impl Interface {
         self.ptr.unwrap()
     }
 
-    pub fn get_connect(&self) -> Result<Connect, Error> {
+    /// The connection the object belongs to. libvirt doesn't take a
+    /// reference for the caller, so the connection is borrowed from
+    /// `self`; clone it to keep it longer.
+    pub fn get_connect<'a>(&'a self) -> Result<Borrowed<'a, Connect>, Error> {
         unsafe {
             let ptr = sys::virInterfaceGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::last_error());
             }
-            return Ok(Connect::new(ptr));
+            return Ok(Borrowed::new(Connect::new(ptr)));
         }
     }
 
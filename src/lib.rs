@@ -46,8 +46,19 @@ macro_rules! impl_from {
 pub mod connect;
 pub mod domain;
 pub mod error;
+pub mod event;
+pub mod handle;
 pub mod secret;
 pub mod stream;
 pub mod interface;
 pub mod storage_vol;
 pub mod storage_pool;
+
+#[cfg(feature = "qemu")]
+pub mod qemu;
+
+#[cfg(feature = "lxc")]
+pub mod lxc;
+
+mod typed_params;
+mod xmlutil;
@@ -0,0 +1,198 @@
+extern crate libc;
+extern crate libvirt_sys as sys;
+use error::{Error, ErrorDomain, ErrorNumber};
+
+use connect::SecurityModel;
+use domain::{Domain, SecurityLabel};
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::panic::{self, AssertUnwindSafe};
+use std::{io, mem, ptr, slice};
+
+#[link(name = "virt-lxc")]
+extern "C" {
+    fn virDomainLxcOpenNamespace(domain: sys::virDomainPtr,
+                                 fdlist: *mut *mut libc::c_int,
+                                 flags: libc::c_uint)
+                                 -> libc::c_int;
+    fn virDomainLxcEnterNamespace(domain: sys::virDomainPtr,
+                                  nfdlist: libc::c_uint,
+                                  fdlist: *mut libc::c_int,
+                                  noldfdlist: *mut libc::c_uint,
+                                  oldfdlist: *mut *mut libc::c_int,
+                                  flags: libc::c_uint)
+                                  -> libc::c_int;
+    fn virDomainLxcEnterSecurityLabel(model: sys::virSecurityModelPtr,
+                                      label: sys::virSecurityLabelPtr,
+                                      oldlabel: sys::virSecurityLabelPtr,
+                                      flags: libc::c_uint)
+                                      -> libc::c_int;
+    fn virDomainLxcEnterCGroup(domain: sys::virDomainPtr, flags: libc::c_uint) -> libc::c_int;
+}
+
+/// Copy `src` into a fixed-size C string buffer, truncating if needed.
+fn copy_to_c_buf(src: &str, buf: &mut [libc::c_char]) {
+    let len = ::std::cmp::min(src.len(), buf.len() - 1);
+    for (i, b) in src.as_bytes()[..len].iter().enumerate() {
+        buf[i] = *b as libc::c_char;
+    }
+    buf[len] = 0;
+}
+
+/// Take ownership of a malloc'd array of file descriptors.
+unsafe fn fds_from_array(fds: *mut libc::c_int, n: usize) -> Vec<OwnedFd> {
+    if fds.is_null() {
+        return Vec::new();
+    }
+    let owned = slice::from_raw_parts(fds, n).iter().map(|fd| OwnedFd::from_raw_fd(*fd)).collect();
+    libc::free(fds as *mut libc::c_void);
+    owned
+}
+
+impl Domain {
+    /// Open the namespaces of the container's init process. The
+    /// returned file descriptors can be passed to
+    /// `lxc_enter_namespace()`.
+    pub fn lxc_open_namespace(&self) -> Result<Vec<OwnedFd>, Error> {
+        unsafe {
+            let mut fdlist: *mut libc::c_int = ptr::null_mut();
+            let size = virDomainLxcOpenNamespace(self.as_ptr(), &mut fdlist, 0);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(fds_from_array(fdlist, size as usize));
+        }
+    }
+
+    /// Attach the calling process to the namespaces in `fds`, as
+    /// returned by `lxc_open_namespace()`. Returns file descriptors
+    /// for the namespaces the process was previously in.
+    ///
+    /// Entering a PID namespace only affects children forked
+    /// afterwards, not the calling process itself.
+    pub fn lxc_enter_namespace(&self, fds: &[OwnedFd]) -> Result<Vec<OwnedFd>, Error> {
+        let mut raw: Vec<libc::c_int> = fds.iter().map(|fd| fd.as_raw_fd()).collect();
+        unsafe {
+            let mut noldfdlist: libc::c_uint = 0;
+            let mut oldfdlist: *mut libc::c_int = ptr::null_mut();
+            let ret = virDomainLxcEnterNamespace(self.as_ptr(),
+                                                 raw.len() as libc::c_uint,
+                                                 raw.as_mut_ptr(),
+                                                 &mut noldfdlist,
+                                                 &mut oldfdlist,
+                                                 0);
+            if ret == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(fds_from_array(oldfdlist, noldfdlist as usize));
+        }
+    }
+
+    /// Apply the security label `label` of the given security model
+    /// to the calling process. Returns the label the process had
+    /// before.
+    pub fn lxc_enter_security_label(model: &SecurityModel, label: &SecurityLabel) -> Result<SecurityLabel, Error> {
+        unsafe {
+            let mut secmodel: sys::virSecurityModel = mem::zeroed();
+            copy_to_c_buf(&model.model, &mut secmodel.model);
+            copy_to_c_buf(&model.doi, &mut secmodel.doi);
+
+            let mut seclabel: sys::virSecurityLabel = mem::zeroed();
+            copy_to_c_buf(&label.label, &mut seclabel.label);
+            seclabel.enforcing = label.enforcing as libc::c_int;
+
+            let mut oldlabel: sys::virSecurityLabel = mem::zeroed();
+            if virDomainLxcEnterSecurityLabel(&mut secmodel, &mut seclabel, &mut oldlabel, 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(SecurityLabel::from_ptr(&mut oldlabel));
+        }
+    }
+
+    /// Move the calling process into the cgroups of the container.
+    pub fn lxc_enter_cgroup(&self) -> Result<(), Error> {
+        unsafe {
+            if virDomainLxcEnterCGroup(self.as_ptr(), 0) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Run `f` inside the container, and return its exit status.
+    ///
+    /// The closure runs in a forked child which has joined the
+    /// container's cgroups and namespaces and, if `with_label` is set,
+    /// taken on its security label. A panic in `f` gives exit status
+    /// 255.
+    ///
+    /// Only async-signal-safe functions are safe to call in a child
+    /// forked from a multi-threaded process, and joining the container
+    /// already goes beyond that: libvirt and the error handling here
+    /// allocate memory. If another thread holds the allocator lock at
+    /// the time of the fork, the child may deadlock. Keep `f` short,
+    /// such as a call to `exec`, and avoid this function in programs
+    /// which allocate heavily from other threads.
+    pub fn lxc_run_in_namespace<F>(&self, with_label: bool, f: F) -> Result<i32, Error>
+        where F: FnOnce() -> i32
+    {
+        let fds = self.lxc_open_namespace()?;
+        let labels = if with_label {
+            let model = self.get_connect()?.get_security_model()?;
+            let label = self.get_security_label()?;
+            Some((model, label))
+        } else {
+            None
+        };
+
+        unsafe {
+            let pid = libc::fork();
+            if pid < 0 {
+                return Err(Error::new(ErrorNumber::SystemError,
+                                      ErrorDomain::None,
+                                      format!("fork failed: {}", io::Error::last_os_error())));
+            }
+
+            if pid == 0 {
+                if self.lxc_enter_cgroup().is_err() || self.lxc_enter_namespace(&fds).is_err() {
+                    libc::_exit(255);
+                }
+                if let Some((ref model, ref label)) = labels {
+                    if Domain::lxc_enter_security_label(model, label).is_err() {
+                        libc::_exit(255);
+                    }
+                }
+
+                // Fork again so the closure runs inside the PID
+                // namespace as well.
+                let inner = libc::fork();
+                if inner < 0 {
+                    libc::_exit(255);
+                }
+                if inner == 0 {
+                    // Unwinding would run the parent's destructors, such
+                    // as closing its libvirt connections, in this copy.
+                    libc::_exit(panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(255));
+                }
+                libc::_exit(wait_for(inner));
+            }
+
+            return Ok(wait_for(pid));
+        }
+    }
+}
+
+/// Wait for the child `pid` and translate its status into an exit
+/// code, using 255 for abnormal termination.
+unsafe fn wait_for(pid: libc::pid_t) -> i32 {
+    let mut status: libc::c_int = 0;
+    while libc::waitpid(pid, &mut status, 0) == -1 {
+        if io::Error::last_os_error().raw_os_error() != Some(libc::EINTR) {
+            return 255;
+        }
+    }
+    if libc::WIFEXITED(status) {
+        libc::WEXITSTATUS(status)
+    } else {
+        255
+    }
+}
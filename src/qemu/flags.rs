@@ -0,0 +1,16 @@
+bitflags! {
+    pub struct QemuMonitorCommandFlags: u32 {
+        const DEFAULT = 0;
+        /// cmd is in HMP
+        const HMP = 1;
+    }
+}
+
+bitflags! {
+    pub struct QemuMonitorEventRegisterFlags: u32 {
+        /// Event is a regular expression instead of an exact name
+        const REGEX = 1;
+        /// Event name matching is case-insensitive
+        const NOCASE = 2;
+    }
+}
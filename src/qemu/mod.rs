@@ -0,0 +1,208 @@
+extern crate libc;
+extern crate libvirt_sys as sys;
+use error::{Error, ErrorDomain, ErrorNumber};
+use event::EventRegistration;
+use handle::Borrowed;
+
+use connect::Connect;
+use domain::Domain;
+use std::ffi::CString;
+use std::panic::{self, AssertUnwindSafe};
+use std::{mem, ptr};
+
+pub mod flags;
+use qemu::flags::*;
+
+#[link(name = "virt-qemu")]
+extern "C" {
+    fn virDomainQemuMonitorCommand(domain: sys::virDomainPtr,
+                                   cmd: *const libc::c_char,
+                                   result: *mut *mut libc::c_char,
+                                   flags: libc::c_uint)
+                                   -> libc::c_int;
+    fn virDomainQemuAgentCommand(domain: sys::virDomainPtr,
+                                 cmd: *const libc::c_char,
+                                 timeout: libc::c_int,
+                                 flags: libc::c_uint)
+                                 -> *mut libc::c_char;
+    fn virDomainQemuAttach(conn: sys::virConnectPtr,
+                           pid_value: libc::c_uint,
+                           flags: libc::c_uint)
+                           -> sys::virDomainPtr;
+    fn virConnectDomainQemuMonitorEventRegister(conn: sys::virConnectPtr,
+                                                dom: sys::virDomainPtr,
+                                                event: *const libc::c_char,
+                                                cb: QemuMonitorEventCallback,
+                                                opaque: *mut libc::c_void,
+                                                freecb: sys::virFreeCallback,
+                                                flags: libc::c_uint)
+                                                -> libc::c_int;
+    fn virConnectDomainQemuMonitorEventDeregister(conn: sys::virConnectPtr,
+                                                  callback_id: libc::c_int)
+                                                  -> libc::c_int;
+}
+
+type QemuMonitorEventCallback = Option<unsafe extern "C" fn(conn: sys::virConnectPtr,
+                                                            dom: sys::virDomainPtr,
+                                                            event: *const libc::c_char,
+                                                            seconds: libc::c_longlong,
+                                                            micros: libc::c_uint,
+                                                            details: *const libc::c_char,
+                                                            opaque: *mut libc::c_void)>;
+
+/// How long `Domain::qemu_agent_command()` waits for the guest agent
+/// to answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QemuAgentCommandTimeout {
+    /// Block until the agent answers
+    Block,
+    /// Use the hypervisor's default timeout
+    Default,
+    /// Don't wait for an answer at all
+    NoWait,
+    /// Wait at most the given number of seconds, up to `i32::MAX`
+    Seconds(u32),
+}
+
+impl QemuAgentCommandTimeout {
+    /// The timeout as passed to libvirt. Fails if `Seconds` doesn't fit
+    /// in an `i32`, as it would turn into one of the negative values
+    /// libvirt reserves for `Block` and `Default`.
+    pub fn to_int(&self) -> Result<i32, Error> {
+        match *self {
+            QemuAgentCommandTimeout::Block => Ok(-2),
+            QemuAgentCommandTimeout::Default => Ok(-1),
+            QemuAgentCommandTimeout::NoWait => Ok(0),
+            QemuAgentCommandTimeout::Seconds(s) if s <= i32::MAX as u32 => Ok(s as i32),
+            QemuAgentCommandTimeout::Seconds(s) => {
+                Err(Error::new(ErrorNumber::InvalidArg,
+                               ErrorDomain::Qemu,
+                               format!("agent command timeout of {} seconds is too large", s)))
+            }
+        }
+    }
+}
+
+/// An event emitted by the QEMU monitor of a domain.
+#[derive(Clone, Debug)]
+pub struct QemuMonitorEvent {
+    /// Name of the event, such as "SHUTDOWN".
+    pub event: String,
+    /// Time of the event, in seconds since the epoch.
+    pub seconds: i64,
+    /// Microseconds part of the event time.
+    pub micros: u32,
+    /// JSON string of the event details, if any.
+    pub details: Option<String>,
+}
+
+impl Domain {
+    /// Send an arbitrary monitor command to the QEMU process backing
+    /// the domain and return its reply. The command is QMP JSON, or
+    /// HMP text with `QemuMonitorCommandFlags::HMP`.
+    ///
+    /// This bypasses libvirt's view of the domain, so use it for
+    /// debugging only.
+    pub fn qemu_monitor_command(&self, cmd: &str, flags: QemuMonitorCommandFlags) -> Result<String, Error> {
+        unsafe {
+            let mut result: *mut libc::c_char = ptr::null_mut();
+            if virDomainQemuMonitorCommand(self.as_ptr(), string_to_c_chars!(cmd), &mut result, flags.bits()) == -1 {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(result));
+        }
+    }
+
+    /// Send a raw JSON command to the guest agent and return its
+    /// reply.
+    pub fn qemu_agent_command(&self, cmd: &str, timeout: QemuAgentCommandTimeout) -> Result<String, Error> {
+        let timeout = timeout.to_int()?;
+        unsafe {
+            let result = virDomainQemuAgentCommand(self.as_ptr(),
+                                                   string_to_c_chars!(cmd),
+                                                   timeout as libc::c_int,
+                                                   0);
+            if result.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(c_chars_to_string!(result));
+        }
+    }
+
+    /// Attach libvirt to an existing QEMU process with the given pid.
+    pub fn qemu_attach(conn: &Connect, pid: u32) -> Result<Domain, Error> {
+        unsafe {
+            let ptr = virDomainQemuAttach(conn.as_ptr(), pid as libc::c_uint, 0);
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(Domain::new(ptr));
+        }
+    }
+}
+
+impl Connect {
+    /// Register a callback for QEMU monitor events. `dom` restricts
+    /// the events to a single domain and `event` to a single event
+    /// name (or pattern, see `QemuMonitorEventRegisterFlags`).
+    ///
+    /// An event loop must be registered and run, see
+    /// `event::event_register_default_impl()`. The callback stays
+    /// registered until the returned `EventRegistration` is dropped.
+    pub fn domain_qemu_monitor_event_register<F>(&self,
+                                                 dom: Option<&Domain>,
+                                                 event: Option<&str>,
+                                                 callback: F,
+                                                 flags: QemuMonitorEventRegisterFlags)
+                                                 -> Result<EventRegistration, Error>
+        where F: FnMut(&Connect, &Domain, QemuMonitorEvent) + Send + 'static
+    {
+        unsafe extern "C" fn event_callback<F>(conn: sys::virConnectPtr,
+                                               dom: sys::virDomainPtr,
+                                               event: *const libc::c_char,
+                                               seconds: libc::c_longlong,
+                                               micros: libc::c_uint,
+                                               details: *const libc::c_char,
+                                               opaque: *mut libc::c_void)
+            where F: FnMut(&Connect, &Domain, QemuMonitorEvent)
+        {
+            let f = opaque as *mut F;
+            // Both handles are borrowed from libvirt for the duration of
+            // the callback.
+            let conn = Borrowed::new(Connect::new(conn));
+            let dom = Borrowed::new(Domain::new(dom));
+            // Unwinding into libvirt's event loop is undefined
+            // behaviour, so a panic in the callback is dropped here.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                let event = QemuMonitorEvent {
+                    event: c_chars_to_string!(event, nofree),
+                    seconds: seconds as i64,
+                    micros: micros as u32,
+                    details: if details.is_null() { None } else { Some(c_chars_to_string!(details, nofree)) },
+                };
+                (*f)(&conn, &dom, event);
+            }));
+        }
+
+        unsafe extern "C" fn free_callback<F>(opaque: *mut libc::c_void) {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(opaque as *mut F))));
+        }
+
+        let event = event.map(|e| CString::new(e).unwrap());
+        let data = Box::into_raw(Box::new(callback));
+        unsafe {
+            let ret = virConnectDomainQemuMonitorEventRegister(self.as_ptr(),
+                                                               dom.map_or(ptr::null_mut(), |d| d.as_ptr()),
+                                                               event.as_ref().map_or(ptr::null(), |e| e.as_ptr()),
+                                                               Some(event_callback::<F>),
+                                                               data as *mut libc::c_void,
+                                                               Some(free_callback::<F>),
+                                                               flags.bits());
+            if ret == -1 {
+                drop(Box::from_raw(data));
+                return Err(Error::last_error());
+            }
+            return Ok(EventRegistration::new(self.as_ptr(), ret as i32, virConnectDomainQemuMonitorEventDeregister));
+        }
+    }
+}
@@ -14,3 +14,38 @@ bitflags! {
         const VIR_SECRET_USAGE_TYPE_LAST = 5;
     }
 }
+
+/// Secret events which can be registered with
+/// `Connect::secret_event_register_any()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecretEventId {
+    Lifecycle = 0,
+    ValueChanged = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecretLifecycleEvent {
+    Defined,
+    Undefined,
+    /// An event type not known to this version of the bindings
+    Other(i32),
+}
+
+impl SecretLifecycleEvent {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => SecretLifecycleEvent::Defined,
+            1 => SecretLifecycleEvent::Undefined,
+            n => SecretLifecycleEvent::Other(n),
+        }
+    }
+}
+
+/// An event delivered to callbacks registered with
+/// `Connect::secret_event_register_any()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecretEvent {
+    Lifecycle(SecretLifecycleEvent),
+    /// The secret's value was set.
+    ValueChanged,
+}
@@ -3,6 +3,7 @@ extern crate libvirt_sys as sys;
 use error::Error;
 
 use connect::Connect;
+use handle::Borrowed;
 
 pub mod flags;
 use secret::flags::*;
@@ -15,6 +16,32 @@ pub struct Secret {
     ptr: Option<sys::virSecretPtr>,
 }
 
+impl Drop for Secret {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.free() {
+                panic!("Unable to drop memory for Secret, code {}, message: {}", e.code, e.message)
+            }
+        }
+    }
+}
+
+impl Clone for Secret {
+    /// Take a new reference to the same object.
+    fn clone(&self) -> Secret {
+        unsafe {
+            if sys::virSecretRef(self.as_ptr()) == -1 {
+                let e = Error::last_error();
+                panic!("Unable to add reference to Secret, code {}, message: {}", e.code, e.message)
+            }
+        }
+        Secret::new(self.as_ptr())
+    }
+}
+
+unsafe impl Send for Secret {}
+unsafe impl Sync for Secret {}
+
 impl Secret {
     pub fn new(ptr: sys::virSecretPtr) -> Secret {
         return Secret { ptr: Some(ptr) };
@@ -24,13 +51,16 @@ impl Secret {
         self.ptr.unwrap()
     }
 
-    pub fn get_connect(&self) -> Result<Connect, Error> {
+    /// The connection the object belongs to. libvirt doesn't take a
+    /// reference for the caller, so the connection is borrowed from
+    /// `self`; clone it to keep it longer.
+    pub fn get_connect<'a>(&'a self) -> Result<Borrowed<'a, Connect>, Error> {
         unsafe {
             let ptr = sys::virSecretGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::last_error());
             }
-            return Ok(Connect::new(ptr));
+            return Ok(Borrowed::new(Connect::new(ptr)));
         }
     }
 
@@ -0,0 +1,235 @@
+//! Fluent construction of storage pool definitions.
+//!
+//! ```no_run
+//! # use new_libvirt::connect::Connect;
+//! # use new_libvirt::storage_pool::builder::StoragePoolBuilder;
+//! let conn = Connect::open("qemu:///system").unwrap();
+//! let pool = StoragePoolBuilder::netfs("isos", "nas.example.com", "/export/isos", "/var/lib/libvirt/isos")
+//!     .source_format("nfs")
+//!     .define(&conn)
+//!     .unwrap();
+//! ```
+
+use connect::Connect;
+use error::Error;
+use storage_pool::StoragePool;
+use storage_pool::flags::StoragePoolCreateFlags;
+use storage_pool::xml::*;
+
+/// Builds a `StoragePoolConfig`. Each pool type has its own
+/// constructor taking the settings it can't do without; the other
+/// settings are chained on.
+#[derive(Clone, Debug)]
+pub struct StoragePoolBuilder {
+    config: StoragePoolConfig,
+}
+
+impl StoragePoolBuilder {
+    fn new(pool_type: &str, name: &str) -> StoragePoolBuilder {
+        StoragePoolBuilder { config: StoragePoolConfig::new(pool_type, name) }
+    }
+
+    fn source(&mut self) -> &mut PoolSource {
+        if self.config.source.is_none() {
+            self.config.source = Some(PoolSource::default());
+        }
+        self.config.source.as_mut().unwrap()
+    }
+
+    fn target(&mut self) -> &mut PoolTarget {
+        if self.config.target.is_none() {
+            self.config.target = Some(PoolTarget::default());
+        }
+        self.config.target.as_mut().unwrap()
+    }
+
+    /// A directory on the host.
+    pub fn dir(name: &str, path: &str) -> StoragePoolBuilder {
+        StoragePoolBuilder::new("dir", name).target_path(path)
+    }
+
+    /// A block device holding a filesystem, mounted on `path`.
+    pub fn fs(name: &str, device: &str, path: &str) -> StoragePoolBuilder {
+        StoragePoolBuilder::new("fs", name).device(device).target_path(path)
+    }
+
+    /// A directory exported by `host`, mounted on `path`.
+    pub fn netfs(name: &str, host: &str, dir: &str, path: &str) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new("netfs", name).host(host, None).target_path(path);
+        b.source().dir = Some(dir.to_string());
+        b
+    }
+
+    /// An LVM volume group.
+    pub fn logical(name: &str, volume_group: &str) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new("logical", name).target_path(&format!("/dev/{}", volume_group));
+        b.source().name = Some(volume_group.to_string());
+        b
+    }
+
+    /// A partitioned disk, with one volume per partition.
+    pub fn disk(name: &str, device: &str) -> StoragePoolBuilder {
+        StoragePoolBuilder::new("disk", name).device(device).target_path("/dev")
+    }
+
+    /// The LUNs of an iSCSI target `iqn` on `host`.
+    pub fn iscsi(name: &str, host: &str, iqn: &str) -> StoragePoolBuilder {
+        StoragePoolBuilder::new("iscsi", name)
+            .host(host, None)
+            .device(iqn)
+            .target_path("/dev/disk/by-path")
+    }
+
+    /// The images of the Ceph pool `rbd_pool`, with `host` as a
+    /// monitor. Add more monitors with `host()`.
+    pub fn rbd(name: &str, host: &str, rbd_pool: &str) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new("rbd", name).host(host, None);
+        b.source().name = Some(rbd_pool.to_string());
+        b
+    }
+
+    /// The volumes of the ZFS pool `zpool`.
+    pub fn zfs(name: &str, zpool: &str) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new("zfs", name);
+        b.source().name = Some(zpool.to_string());
+        b
+    }
+
+    /// The directory `dir` of the Gluster volume `volume` on `host`.
+    pub fn gluster(name: &str, host: &str, volume: &str, dir: &str) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new("gluster", name).host(host, None);
+        b.source().name = Some(volume.to_string());
+        b.source().dir = Some(dir.to_string());
+        b
+    }
+
+    /// A pool of type `pool_type` using a source found by
+    /// `Connect::find_storage_pool_sources()`.
+    pub fn from_source(pool_type: &str, name: &str, source: PoolSource) -> StoragePoolBuilder {
+        let mut b = StoragePoolBuilder::new(pool_type, name);
+        b.config.source = Some(source);
+        b
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> StoragePoolBuilder {
+        self.config.uuid = Some(uuid.to_string());
+        self
+    }
+
+    /// Add a source host.
+    pub fn host(mut self, name: &str, port: Option<u16>) -> StoragePoolBuilder {
+        self.source().hosts.push(SourceHost {
+            name: Some(name.to_string()),
+            port: port,
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    /// Add a source device.
+    pub fn device(mut self, path: &str) -> StoragePoolBuilder {
+        self.source().devices.push(path.to_string());
+        self
+    }
+
+    /// Filesystem or partition table format of the source, such as
+    /// "ext4", "nfs", "lvm2" or "gpt".
+    pub fn source_format(mut self, format: &str) -> StoragePoolBuilder {
+        self.source().format = Some(format.to_string());
+        self
+    }
+
+    /// Authenticate to an iSCSI target with CHAP, using the password
+    /// stored in the secret `secret_uuid`.
+    pub fn auth_chap(self, username: &str, secret_uuid: &str) -> StoragePoolBuilder {
+        self.auth("chap", username, secret_uuid)
+    }
+
+    /// Authenticate to Ceph with cephx, using the key stored in the
+    /// secret `secret_uuid`.
+    pub fn auth_ceph(self, username: &str, secret_uuid: &str) -> StoragePoolBuilder {
+        self.auth("ceph", username, secret_uuid)
+    }
+
+    fn auth(mut self, auth_type: &str, username: &str, secret_uuid: &str) -> StoragePoolBuilder {
+        self.source().auth = Some(SourceAuth {
+            auth_type: Some(auth_type.to_string()),
+            username: Some(username.to_string()),
+            secret_uuid: Some(secret_uuid.to_string()),
+            secret_usage: None,
+            secret_extra: Extra::default(),
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    /// IQN the host uses to log in to an iSCSI target.
+    pub fn initiator(mut self, iqn: &str) -> StoragePoolBuilder {
+        self.source().initiator = Some(iqn.to_string());
+        self
+    }
+
+    pub fn target_path(mut self, path: &str) -> StoragePoolBuilder {
+        self.target().path = Some(path.to_string());
+        self
+    }
+
+    /// Mode and ownership of the target directory.
+    pub fn permissions(mut self, mode: u32, owner: u32, group: u32) -> StoragePoolBuilder {
+        self.target().permissions = Some(Permissions {
+            mode: Some(format!("{:04o}", mode)),
+            owner: Some(owner),
+            group: Some(group),
+            label: None,
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    pub fn build(self) -> StoragePoolConfig {
+        self.config
+    }
+
+    pub fn to_xml(&self) -> String {
+        self.config.to_xml()
+    }
+
+    /// Define a persistent pool on `conn`, without starting it.
+    pub fn define(&self, conn: &Connect) -> Result<StoragePool, Error> {
+        StoragePool::define(conn, &self.config)
+    }
+
+    /// Create and start a transient pool on `conn`.
+    pub fn create(&self, conn: &Connect, flags: StoragePoolCreateFlags) -> Result<StoragePool, Error> {
+        StoragePool::create_xml(conn, &self.config.to_xml(), flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xmlutil::parse;
+
+    #[test]
+    fn from_found_source() {
+        let sources = parse_pool_sources("<sources>
+  <source>
+    <device path='/dev/sdb1'/>
+    <device path='/dev/sdc1'/>
+    <name>data</name>
+    <format type='lvm2'/>
+  </source>
+</sources>").unwrap();
+        let xml = StoragePoolBuilder::from_source("logical", "data", sources[0].clone())
+            .target_path("/dev/data")
+            .to_xml();
+        let el = parse(&xml).unwrap();
+        let names: Vec<_> = el.children.iter().filter_map(|n| n.as_element()).map(|c| &c.name[..]).collect();
+        assert_eq!(names, vec!["name", "source", "target"]);
+        let config = StoragePoolConfig::from_xml(&xml).unwrap();
+        assert_eq!(config.pool_type, "logical");
+        assert_eq!(config.name, "data");
+        assert_eq!(config.source.as_ref(), Some(&sources[0]));
+        assert_eq!(config.target.unwrap().path.unwrap(), "/dev/data");
+    }
+}
@@ -65,3 +65,46 @@ bitflags! {
         const INACTIVE = 1;
     }
 }
+
+/// Storage pool events which can be registered with
+/// `Connect::storage_pool_event_register_any()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StoragePoolEventId {
+    Lifecycle = 0,
+    Refresh = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StoragePoolLifecycleEvent {
+    Defined,
+    Undefined,
+    Started,
+    Stopped,
+    Created,
+    Deleted,
+    /// An event type not known to this version of the bindings
+    Other(i32),
+}
+
+impl StoragePoolLifecycleEvent {
+    pub fn from_int(n: i32) -> Self {
+        match n {
+            0 => StoragePoolLifecycleEvent::Defined,
+            1 => StoragePoolLifecycleEvent::Undefined,
+            2 => StoragePoolLifecycleEvent::Started,
+            3 => StoragePoolLifecycleEvent::Stopped,
+            4 => StoragePoolLifecycleEvent::Created,
+            5 => StoragePoolLifecycleEvent::Deleted,
+            n => StoragePoolLifecycleEvent::Other(n),
+        }
+    }
+}
+
+/// An event delivered to callbacks registered with
+/// `Connect::storage_pool_event_register_any()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StoragePoolEvent {
+    Lifecycle(StoragePoolLifecycleEvent),
+    /// The pool was refreshed, so its volume list may have changed.
+    Refresh,
+}
@@ -2,10 +2,14 @@ extern crate libc;
 extern crate libvirt_sys as sys;
 
 use connect::Connect;
+use handle::Borrowed;
 use storage_vol::StorageVol;
 use error::Error;
+use std::ptr;
 
+pub mod builder;
 pub mod flags;
+pub mod xml;
 use storage_pool::flags::*;
 
 #[derive(Debug)]
@@ -46,6 +50,22 @@ impl Drop for StoragePool {
     }
 }
 
+impl Clone for StoragePool {
+    /// Take a new reference to the same object.
+    fn clone(&self) -> StoragePool {
+        unsafe {
+            if sys::virStoragePoolRef(self.as_ptr()) == -1 {
+                let e = Error::last_error();
+                panic!("Unable to add reference to StoragePool, code {}, message: {}", e.code, e.message)
+            }
+        }
+        StoragePool::new(self.as_ptr())
+    }
+}
+
+unsafe impl Send for StoragePool {}
+unsafe impl Sync for StoragePool {}
+
 impl StoragePool {
     pub fn new(ptr: sys::virStoragePoolPtr) -> StoragePool {
         return StoragePool { ptr: Some(ptr) };
@@ -55,13 +75,16 @@ impl StoragePool {
         self.ptr.unwrap()
     }
 
-    pub fn get_connect(&self) -> Result<Connect, Error> {
+    /// The connection the object belongs to. libvirt doesn't take a
+    /// reference for the caller, so the connection is borrowed from
+    /// `self`; clone it to keep it longer.
+    pub fn get_connect<'a>(&'a self) -> Result<Borrowed<'a, Connect>, Error> {
         unsafe {
             let ptr = sys::virStoragePoolGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::last_error());
             }
-            return Ok(Connect::new(ptr));
+            return Ok(Borrowed::new(Connect::new(ptr)));
         }
     }
     pub fn define_xml(conn: &Connect, xml: &str) -> Result<StoragePool, Error> {
@@ -94,6 +117,8 @@ impl StoragePool {
         }
     }
 
+    /// Find the pool containing `vol`. The pool holds its own
+    /// reference, so it may outlive `vol`.
     pub fn lookup_by_volume(vol: &StorageVol) -> Result<StoragePool, Error> {
         unsafe {
             let ptr = sys::virStoragePoolLookupByVolume(vol.as_ptr());
@@ -104,6 +129,17 @@ impl StoragePool {
         }
     }
 
+    /// Find the pool whose target is the directory `path`.
+    pub fn lookup_by_target_path(conn: &Connect, path: &str) -> Result<StoragePool, Error> {
+        unsafe {
+            let ptr = sys::virStoragePoolLookupByTargetPath(conn.as_ptr(), string_to_c_chars!(path));
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(StoragePool::new(ptr));
+        }
+    }
+
     pub fn lookup_by_uuid_string(conn: &Connect, uuid: &str) -> Result<StoragePool, Error> {
         unsafe {
             let ptr = sys::virStoragePoolLookupByUUIDString(conn.as_ptr(), string_to_c_chars!(uuid));
@@ -134,6 +170,43 @@ impl StoragePool {
         }
     }
 
+    /// Collect the names of the volumes in the pool.
+    pub fn list_volumes(&self) -> Result<Vec<String>, Error> {
+        let max = self.num_of_volumes()?;
+        unsafe {
+            let mut names: Vec<*mut libc::c_char> = vec![ptr::null_mut(); max as usize];
+            let size = sys::virStoragePoolListVolumes(self.as_ptr(), names.as_mut_ptr(), max as libc::c_int);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<String> = Vec::new();
+            for x in 0..size as usize {
+                array.push(c_chars_to_string!(names[x]));
+            }
+            return Ok(array);
+        }
+    }
+
+    /// Collect the volumes in the pool.
+    pub fn list_all_volumes(&self) -> Result<Vec<StorageVol>, Error> {
+        unsafe {
+            let mut vols: *mut sys::virStorageVolPtr = ptr::null_mut();
+            let size = sys::virStoragePoolListAllVolumes(self.as_ptr(), &mut vols, 0);
+            if size == -1 {
+                return Err(Error::last_error());
+            }
+
+            let mut array: Vec<StorageVol> = Vec::new();
+            for x in 0..size as isize {
+                array.push(StorageVol::new(*vols.offset(x)));
+            }
+            libc::free(vols as *mut libc::c_void);
+
+            return Ok(array);
+        }
+    }
+
     pub fn get_uuid_string(&self) -> Result<String, Error> {
         unsafe {
             let mut uuid: [libc::c_char; 37] = [0; 37];
@@ -0,0 +1,662 @@
+//! Typed model of the storage pool XML format.
+//!
+//! See: http://libvirt.org/formatstorage.html
+//!
+//! As with `domain::xml`, anything not modelled as a field is kept in
+//! the `extra` field of the enclosing struct.
+
+use connect::Connect;
+use error::Error;
+use storage_pool::StoragePool;
+use storage_pool::flags::StoragePoolXmlFlags;
+use xmlutil::*;
+
+pub use domain::xml::{Memory, MemoryUnit};
+use domain::xml::{take_memory, push_memory};
+pub use xmlutil::{Element, Extra, XmlModel};
+
+/// Ownership and mode of a pool target, volume or backing store,
+/// `<permissions>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Permissions {
+    /// Octal mode, such as "0711".
+    pub mode: Option<String>,
+    pub owner: Option<u32>,
+    pub group: Option<u32>,
+    /// MAC label, such as an SELinux context.
+    pub label: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Permissions {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "permissions")?;
+        Ok(Permissions {
+            mode: take_child_text(&mut el, "mode"),
+            owner: take_child_parse(&mut el, "owner")?,
+            group: take_child_parse(&mut el, "group")?,
+            label: take_child_text(&mut el, "label"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("permissions");
+        push_text(&mut el, "mode", &self.mode);
+        push_text(&mut el, "owner", &self.owner);
+        push_text(&mut el, "group", &self.group);
+        push_text(&mut el, "label", &self.label);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A remote host serving the pool source, `<host>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceHost {
+    pub name: Option<String>,
+    pub port: Option<u16>,
+    pub extra: Extra,
+}
+
+impl XmlModel for SourceHost {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "host")?;
+        Ok(SourceHost {
+            name: take_attr(&mut el, "name"),
+            port: take_attr_parse(&mut el, "port")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("host");
+        set_attr(&mut el, "name", &self.name);
+        set_attr(&mut el, "port", &self.port);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Authentication against the pool source, `<auth>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceAuth {
+    /// "chap" for iSCSI or "ceph" for RBD.
+    pub auth_type: Option<String>,
+    pub username: Option<String>,
+    /// UUID of the libvirt secret holding the password or key.
+    pub secret_uuid: Option<String>,
+    /// Usage name of the libvirt secret, as an alternative to the UUID.
+    pub secret_usage: Option<String>,
+    /// Anything else inside `<secret>`.
+    pub secret_extra: Extra,
+    pub extra: Extra,
+}
+
+impl XmlModel for SourceAuth {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "auth")?;
+        let auth_type = take_attr(&mut el, "type");
+        let username = take_attr(&mut el, "username");
+        let (uuid, usage, secret_extra) = match take_child(&mut el, "secret") {
+            Some(mut s) => (take_attr(&mut s, "uuid"), take_attr(&mut s, "usage"), Extra::take(s)),
+            None => (None, None, Extra::default()),
+        };
+        Ok(SourceAuth {
+            auth_type: auth_type,
+            username: username,
+            secret_uuid: uuid,
+            secret_usage: usage,
+            secret_extra: secret_extra,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("auth");
+        set_attr(&mut el, "type", &self.auth_type);
+        set_attr(&mut el, "username", &self.username);
+        if self.secret_uuid.is_some() || self.secret_usage.is_some() || self.extra.had_child("secret") {
+            let mut s = Element::new("secret");
+            set_attr(&mut s, "uuid", &self.secret_uuid);
+            set_attr(&mut s, "usage", &self.secret_usage);
+            self.secret_extra.apply(&mut s);
+            push(&mut el, s);
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Where the pool gets its storage from, `<source>`. Which fields are
+/// used depends on the pool type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolSource {
+    /// Remote hosts for "netfs", "iscsi", "rbd" and "gluster" pools.
+    pub hosts: Vec<SourceHost>,
+    /// Block devices for "fs", "logical", "disk" and "zfs" pools, or
+    /// the iSCSI target IQN for "iscsi" pools.
+    pub devices: Vec<String>,
+    /// Remote directory for "netfs" and "gluster" pools.
+    pub dir: Option<String>,
+    /// Volume group, RBD pool, ZFS pool or Gluster volume name.
+    pub name: Option<String>,
+    /// Filesystem or partition table format, such as "ext4", "nfs",
+    /// "lvm2" or "gpt".
+    pub format: Option<String>,
+    pub auth: Option<SourceAuth>,
+    /// Initiator IQN for "iscsi" pools.
+    pub initiator: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for PoolSource {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "source")?;
+        let hosts = take_models(&mut el, "host")?;
+        let mut devices = Vec::new();
+        while let Some(path) = take_child_attr(&mut el, "device", "path") {
+            devices.push(path);
+        }
+        // Anything but a plain <initiator><iqn name='...'/></initiator>
+        // is left in `extra` untouched.
+        let simple = match el.get_child("initiator") {
+            Some(i) => {
+                i.attributes.is_empty() && i.children.len() == 1 &&
+                i.get_child("iqn").map_or(false, |q| {
+                    q.attributes.len() == 1 && q.attributes.contains_key("name") && q.children.is_empty()
+                })
+            }
+            None => false,
+        };
+        let initiator = if simple {
+            take_child(&mut el, "initiator").and_then(|mut i| take_child_attr(&mut i, "iqn", "name"))
+        } else {
+            None
+        };
+        Ok(PoolSource {
+            hosts: hosts,
+            devices: devices,
+            dir: take_child_attr(&mut el, "dir", "path"),
+            name: take_child_text(&mut el, "name"),
+            format: take_child_attr(&mut el, "format", "type"),
+            auth: take_model(&mut el, "auth")?,
+            initiator: initiator,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        push_models(&mut el, &self.hosts);
+        for dev in &self.devices {
+            push_child_attr(&mut el, "device", "path", &Some(dev));
+        }
+        push_child_attr(&mut el, "dir", "path", &self.dir);
+        push_text(&mut el, "name", &self.name);
+        push_child_attr(&mut el, "format", "type", &self.format);
+        push_model(&mut el, &self.auth);
+        if self.initiator.is_some() {
+            let mut i = Element::new("initiator");
+            push_child_attr(&mut i, "iqn", "name", &self.initiator);
+            push(&mut el, i);
+        }
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Where the pool's volumes show up on the host, `<target>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolTarget {
+    pub path: Option<String>,
+    pub permissions: Option<Permissions>,
+    pub extra: Extra,
+}
+
+impl XmlModel for PoolTarget {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "target")?;
+        Ok(PoolTarget {
+            path: take_child_text(&mut el, "path"),
+            permissions: take_model(&mut el, "permissions")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("target");
+        push_text(&mut el, "path", &self.path);
+        push_model(&mut el, &self.permissions);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A complete storage pool definition, the root `<pool>` element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoragePoolConfig {
+    /// "dir", "fs", "netfs", "logical", "disk", "iscsi", "rbd", "zfs",
+    /// "gluster" and so on.
+    pub pool_type: String,
+    pub name: String,
+    pub uuid: Option<String>,
+    /// Sizes reported by `get_xml_desc()`; ignored when defining.
+    pub capacity: Option<Memory>,
+    pub allocation: Option<Memory>,
+    pub available: Option<Memory>,
+    pub source: Option<PoolSource>,
+    pub target: Option<PoolTarget>,
+    pub extra: Extra,
+}
+
+impl StoragePoolConfig {
+    /// A minimal definition with the given pool type and name.
+    pub fn new(pool_type: &str, name: &str) -> StoragePoolConfig {
+        StoragePoolConfig {
+            pool_type: pool_type.to_string(),
+            name: name.to_string(),
+            uuid: None,
+            capacity: None,
+            allocation: None,
+            available: None,
+            source: None,
+            target: None,
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl XmlModel for StoragePoolConfig {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "pool")?;
+        let pool_type = match take_attr(&mut el, "type") {
+            Some(t) => t,
+            None => return Err(xml_error("missing 'type' attribute in <pool>")),
+        };
+        let name = match take_child_text(&mut el, "name") {
+            Some(n) => n,
+            None => return Err(xml_error("missing <name> in <pool>")),
+        };
+        Ok(StoragePoolConfig {
+            pool_type: pool_type,
+            name: name,
+            uuid: take_child_text(&mut el, "uuid"),
+            capacity: take_memory(&mut el, "capacity")?,
+            allocation: take_memory(&mut el, "allocation")?,
+            available: take_memory(&mut el, "available")?,
+            source: take_model(&mut el, "source")?,
+            target: take_model(&mut el, "target")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("pool");
+        set_attr(&mut el, "type", &Some(&self.pool_type));
+        push_text(&mut el, "name", &Some(&self.name));
+        push_text(&mut el, "uuid", &self.uuid);
+        push_memory(&mut el, "capacity", &self.capacity);
+        push_memory(&mut el, "allocation", &self.allocation);
+        push_memory(&mut el, "available", &self.available);
+        push_model(&mut el, &self.source);
+        push_model(&mut el, &self.target);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+impl StoragePool {
+    /// Fetch the XML description of the pool and parse it into a
+    /// `StoragePoolConfig`.
+    pub fn get_config(&self, flags: StoragePoolXmlFlags) -> Result<StoragePoolConfig, Error> {
+        StoragePoolConfig::from_xml(&self.get_xml_desc(flags)?)
+    }
+
+    /// Define a pool from a typed configuration, see `define_xml()`.
+    pub fn define(conn: &Connect, config: &StoragePoolConfig) -> Result<StoragePool, Error> {
+        StoragePool::define_xml(conn, &config.to_xml())
+    }
+}
+
+/// Parse the `<sources>` document returned by
+/// `Connect::find_storage_pool_sources()`.
+pub fn parse_pool_sources(xml: &str) -> Result<Vec<PoolSource>, Error> {
+    let mut el = parse(xml)?;
+    expect_name(&el, "sources")?;
+    take_models(&mut el, "source")
+}
+
+/// Names listed in `<enum name='...'><value>...</value></enum>`.
+fn enum_values(options: Option<&Element>, name: &str) -> Vec<String> {
+    let options = match options {
+        Some(o) => o,
+        None => return Vec::new(),
+    };
+    options.children
+        .iter()
+        .filter_map(|n| n.as_element())
+        .filter(|e| e.name == "enum" && e.attributes.get("name").map_or(false, |n| n == name))
+        .flat_map(|e| e.children.iter().filter_map(|n| n.as_element()))
+        .filter(|v| v.name == "value")
+        .filter_map(|v| text(v))
+        .collect()
+}
+
+fn default_format(options: Option<&Element>) -> Option<String> {
+    options.and_then(|o| o.get_child("defaultFormat"))
+        .and_then(|f| f.attributes.get("type"))
+        .cloned()
+}
+
+/// What the host supports for one pool type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolTypeCapabilities {
+    pub pool_type: String,
+    pub supported: bool,
+    /// Formats allowed for `PoolSource::format`.
+    pub source_formats: Vec<String>,
+    pub default_source_format: Option<String>,
+    /// Formats allowed for volumes in pools of this type.
+    pub volume_formats: Vec<String>,
+    pub default_volume_format: Option<String>,
+}
+
+/// Storage pool types and formats supported by a host, see
+/// `Connect::get_storage_pool_capabilities()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoragePoolCapabilities {
+    pub pools: Vec<PoolTypeCapabilities>,
+}
+
+impl StoragePoolCapabilities {
+    pub fn from_xml(xml: &str) -> Result<StoragePoolCapabilities, Error> {
+        let el = parse(xml)?;
+        expect_name(&el, "storagepoolCapabilities")?;
+        let mut pools = Vec::new();
+        for p in el.children.iter().filter_map(|n| n.as_element()).filter(|e| e.name == "pool") {
+            let pool_options = p.get_child("poolOptions");
+            let vol_options = p.get_child("volOptions");
+            pools.push(PoolTypeCapabilities {
+                pool_type: p.attributes.get("type").cloned().unwrap_or_default(),
+                supported: p.attributes.get("supported").map_or(false, |s| s == "yes"),
+                source_formats: enum_values(pool_options, "sourceFormatType"),
+                default_source_format: default_format(pool_options),
+                volume_formats: enum_values(vol_options, "targetFormatType"),
+                default_volume_format: default_format(vol_options),
+            });
+        }
+        Ok(StoragePoolCapabilities { pools: pools })
+    }
+
+    /// Names of the pool types the host can use.
+    pub fn supported_pool_types(&self) -> Vec<&str> {
+        self.pools.iter().filter(|p| p.supported).map(|p| p.pool_type.as_str()).collect()
+    }
+
+    /// Capabilities of the pool type `pool_type`.
+    pub fn get(&self, pool_type: &str) -> Option<&PoolTypeCapabilities> {
+        self.pools.iter().find(|p| p.pool_type == pool_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that `xml` survives a parse/serialize round trip.
+    fn assert_round_trip(xml: &str) -> StoragePoolConfig {
+        let config = StoragePoolConfig::from_xml(xml).unwrap();
+        let written = config.to_xml();
+        assert_eq!(parse(&written).unwrap(), parse(xml).unwrap(), "round trip changed the XML:\n{}", written);
+        assert_eq!(StoragePoolConfig::from_xml(&written).unwrap(), config);
+        config
+    }
+
+    #[test]
+    fn dir_pool() {
+        let config = assert_round_trip("<pool type='dir'>
+  <name>default</name>
+  <uuid>4e3a2b1c-0d9e-4f8a-b7c6-d5e4f3a2b1c0</uuid>
+  <capacity unit='bytes'>105089261568</capacity>
+  <allocation unit='bytes'>39217483776</allocation>
+  <available unit='bytes'>65871777792</available>
+  <source>
+  </source>
+  <target>
+    <path>/var/lib/libvirt/images</path>
+    <permissions>
+      <mode>0711</mode>
+      <owner>0</owner>
+      <group>0</group>
+      <label>system_u:object_r:virt_image_t:s0</label>
+    </permissions>
+  </target>
+</pool>");
+        assert_eq!(config.pool_type, "dir");
+        assert_eq!(config.capacity.as_ref().unwrap().to_bytes(MemoryUnit::Bytes).unwrap(), 105089261568);
+        let target = config.target.as_ref().unwrap();
+        assert_eq!(target.path.as_ref().unwrap(), "/var/lib/libvirt/images");
+        let permissions = target.permissions.as_ref().unwrap();
+        assert_eq!(permissions.mode.as_ref().unwrap(), "0711");
+        assert_eq!(permissions.owner, Some(0));
+    }
+
+    #[test]
+    fn logical_pool() {
+        let config = assert_round_trip("<pool type='logical'>
+  <name>vg0</name>
+  <uuid>9a8b7c6d-5e4f-4a3b-2c1d-0e9f8a7b6c5d</uuid>
+  <capacity unit='bytes'>499826819072</capacity>
+  <allocation unit='bytes'>107374182400</allocation>
+  <available unit='bytes'>392452636672</available>
+  <source>
+    <device path='/dev/sdb1'/>
+    <device path='/dev/sdc1'/>
+    <name>vg0</name>
+    <format type='lvm2'/>
+  </source>
+  <target>
+    <path>/dev/vg0</path>
+  </target>
+</pool>");
+        let source = config.source.as_ref().unwrap();
+        assert_eq!(source.devices, vec!["/dev/sdb1", "/dev/sdc1"]);
+        assert_eq!(source.name.as_ref().unwrap(), "vg0");
+        assert_eq!(source.format.as_ref().unwrap(), "lvm2");
+    }
+
+    #[test]
+    fn netfs_pool() {
+        let config = assert_round_trip("<pool type='netfs'>
+  <name>nfs</name>
+  <uuid>0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0</uuid>
+  <capacity unit='bytes'>0</capacity>
+  <allocation unit='bytes'>0</allocation>
+  <available unit='bytes'>0</available>
+  <source>
+    <host name='nfs.example.com'/>
+    <dir path='/export/images'/>
+    <format type='nfs'/>
+    <protocol ver='4'/>
+  </source>
+  <target>
+    <path>/mnt/nfs</path>
+  </target>
+</pool>");
+        let source = config.source.as_ref().unwrap();
+        assert_eq!(source.hosts[0].name.as_ref().unwrap(), "nfs.example.com");
+        assert_eq!(source.dir.as_ref().unwrap(), "/export/images");
+        assert_eq!(source.extra.children[0].name, "protocol");
+    }
+
+    #[test]
+    fn rbd_pool_with_auth() {
+        let config = assert_round_trip("<pool type='rbd'>
+  <name>ceph</name>
+  <uuid>5c4d3e2f-1a0b-4c9d-8e7f-6a5b4c3d2e1f</uuid>
+  <capacity unit='bytes'>0</capacity>
+  <allocation unit='bytes'>0</allocation>
+  <available unit='bytes'>0</available>
+  <source>
+    <host name='mon1.example.com' port='6789'/>
+    <host name='mon2.example.com' port='6789'/>
+    <name>libvirt-pool</name>
+    <auth type='ceph' username='libvirt'>
+      <secret uuid='2ec115d7-3a88-3ceb-bc12-0ac909a6fd87'/>
+    </auth>
+    <config opt='rbd_cache' value='true'/>
+  </source>
+</pool>");
+        let source = config.source.as_ref().unwrap();
+        assert_eq!(source.hosts[1].port, Some(6789));
+        let auth = source.auth.as_ref().unwrap();
+        assert_eq!(auth.auth_type.as_ref().unwrap(), "ceph");
+        assert_eq!(auth.username.as_ref().unwrap(), "libvirt");
+        assert_eq!(auth.secret_uuid.as_ref().unwrap(), "2ec115d7-3a88-3ceb-bc12-0ac909a6fd87");
+    }
+
+    #[test]
+    fn unknown_secret_content_is_kept() {
+        let config = assert_round_trip("<pool type='iscsi'>
+  <name>san</name>
+  <source>
+    <host name='san.example.com'/>
+    <device path='iqn.2013-06.com.example:iscsi-pool'/>
+    <auth type='chap' username='admin'>
+      <secret usage='libvirtiscsi' x-extension='1'>
+        <note>rotated monthly</note>
+      </secret>
+    </auth>
+    <initiator>
+      <iqn name='iqn.2013-06.com.example:client'/>
+    </initiator>
+  </source>
+  <target>
+    <path>/dev/disk/by-path</path>
+  </target>
+</pool>");
+        let source = config.source.as_ref().unwrap();
+        let auth = source.auth.as_ref().unwrap();
+        assert_eq!(auth.secret_usage.as_ref().unwrap(), "libvirtiscsi");
+        assert_eq!(auth.secret_extra.attributes, vec![("x-extension".to_string(), "1".to_string())]);
+        assert_eq!(auth.secret_extra.children[0].name, "note");
+        assert_eq!(source.initiator.as_ref().unwrap(), "iqn.2013-06.com.example:client");
+    }
+
+    #[test]
+    fn logical_pool_sources() {
+        let sources = parse_pool_sources("<sources>
+  <source>
+    <device path='/dev/sda3'/>
+    <name>fedora</name>
+    <format type='lvm2'/>
+  </source>
+  <source>
+    <device path='/dev/sdb1'/>
+    <device path='/dev/sdc1'/>
+    <name>data</name>
+    <format type='lvm2'/>
+  </source>
+</sources>").unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].devices, vec!["/dev/sda3"]);
+        assert_eq!(sources[0].name.as_ref().unwrap(), "fedora");
+        assert_eq!(sources[1].devices, vec!["/dev/sdb1", "/dev/sdc1"]);
+        assert_eq!(sources[1].format.as_ref().unwrap(), "lvm2");
+        assert!(sources[1].extra.is_empty());
+    }
+
+    #[test]
+    fn netfs_pool_sources() {
+        let sources = parse_pool_sources("<sources>
+  <source>
+    <host name='nas.example.com'/>
+    <dir path='/export/isos'/>
+    <format type='nfs'/>
+  </source>
+  <source>
+    <host name='nas.example.com'/>
+    <dir path='/export/images'/>
+    <format type='nfs'/>
+  </source>
+</sources>").unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].hosts[0].name.as_ref().unwrap(), "nas.example.com");
+        assert_eq!(sources[0].dir.as_ref().unwrap(), "/export/isos");
+        assert_eq!(sources[1].dir.as_ref().unwrap(), "/export/images");
+        assert_eq!(sources[1].format.as_ref().unwrap(), "nfs");
+    }
+
+    #[test]
+    fn no_pool_sources() {
+        assert!(parse_pool_sources("<sources>\n</sources>").unwrap().is_empty());
+        assert!(parse_pool_sources("<pool type='dir'/>").is_err());
+    }
+
+    #[test]
+    fn pool_capabilities() {
+        let caps = StoragePoolCapabilities::from_xml("<storagepoolCapabilities>
+  <pool type='dir' supported='yes'>
+    <volOptions>
+      <defaultFormat type='raw'/>
+      <enum name='targetFormatType'>
+        <value>none</value>
+        <value>raw</value>
+        <value>qcow2</value>
+        <value>vmdk</value>
+      </enum>
+    </volOptions>
+  </pool>
+  <pool type='fs' supported='yes'>
+    <poolOptions>
+      <defaultFormat type='auto'/>
+      <enum name='sourceFormatType'>
+        <value>auto</value>
+        <value>ext4</value>
+        <value>xfs</value>
+      </enum>
+    </poolOptions>
+    <volOptions>
+      <defaultFormat type='raw'/>
+      <enum name='targetFormatType'>
+        <value>raw</value>
+        <value>qcow2</value>
+      </enum>
+    </volOptions>
+  </pool>
+  <pool type='logical' supported='yes'>
+    <poolOptions>
+      <defaultFormat type='lvm2'/>
+      <enum name='sourceFormatType'>
+        <value>unknown</value>
+        <value>lvm2</value>
+      </enum>
+    </poolOptions>
+  </pool>
+  <pool type='rbd' supported='no'>
+    <volOptions>
+      <defaultFormat type='raw'/>
+      <enum name='targetFormatType'>
+      </enum>
+    </volOptions>
+  </pool>
+</storagepoolCapabilities>").unwrap();
+        assert_eq!(caps.supported_pool_types(), vec!["dir", "fs", "logical"]);
+        let dir = caps.get("dir").unwrap();
+        assert!(dir.source_formats.is_empty());
+        assert_eq!(dir.default_source_format, None);
+        assert_eq!(dir.volume_formats, vec!["none", "raw", "qcow2", "vmdk"]);
+        assert_eq!(dir.default_volume_format.as_ref().unwrap(), "raw");
+        let fs = caps.get("fs").unwrap();
+        assert_eq!(fs.source_formats, vec!["auto", "ext4", "xfs"]);
+        assert_eq!(fs.default_source_format.as_ref().unwrap(), "auto");
+        let logical = caps.get("logical").unwrap();
+        assert_eq!(logical.default_source_format.as_ref().unwrap(), "lvm2");
+        assert!(logical.volume_formats.is_empty());
+        let rbd = caps.get("rbd").unwrap();
+        assert!(!rbd.supported);
+        assert!(rbd.volume_formats.is_empty());
+        assert!(caps.get("zfs").is_none());
+    }
+}
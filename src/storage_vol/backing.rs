@@ -0,0 +1,85 @@
+//! Backing chains of copy-on-write images.
+
+use error::Error;
+use storage_pool::StoragePool;
+use storage_vol::StorageVol;
+use storage_vol::builder::StorageVolBuilder;
+use storage_vol::flags::StorageVolCreateFlags;
+use storage_vol::xml::{BackingStore, MemoryUnit};
+
+/// One image in a backing chain, starting with the image right below
+/// the volume or disk the chain belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackingImage {
+    /// File or device path of the image. Network images, such as RBD
+    /// images, have a source name instead.
+    pub path: Option<String>,
+    /// Image format, such as "qcow2" or "raw".
+    pub format: Option<String>,
+}
+
+impl<'a> From<&'a BackingStore> for BackingImage {
+    fn from(store: &'a BackingStore) -> BackingImage {
+        BackingImage {
+            path: store.path.clone(),
+            format: store.format.clone(),
+        }
+    }
+}
+
+impl StorageVol {
+    /// Walk the backing chain of the volume.
+    ///
+    /// Volume XML only names the image directly below a volume, so the
+    /// chain is followed through volumes libvirt knows about. It ends at
+    /// the first image without a backing store, or at the first image
+    /// which isn't in any storage pool. Other lookup failures are
+    /// returned as errors.
+    pub fn backing_chain(&self) -> Result<Vec<BackingImage>, Error> {
+        let conn = self.get_connect()?;
+        let mut chain: Vec<BackingImage> = Vec::new();
+        let mut store = self.get_config()?.backing_store;
+        while let Some(s) = store.take() {
+            let image = BackingImage::from(&s);
+            let path = match image.path.clone() {
+                Some(p) => p,
+                None => {
+                    chain.push(image);
+                    break;
+                }
+            };
+            // Guard against a corrupted chain pointing back at itself.
+            if chain.iter().any(|i| i.path.as_ref() == Some(&path)) {
+                break;
+            }
+            chain.push(image);
+            store = match StorageVol::lookup_by_path(&conn, &path) {
+                Ok(vol) => vol.get_config()?.backing_store,
+                Err(ref e) if e.is_no_storage_vol() => None,
+                Err(e) => return Err(e),
+            };
+        }
+        Ok(chain)
+    }
+
+    /// Create a volume named `name` in `pool`, backed by this volume,
+    /// with the same capacity. `format` is the format of the new
+    /// volume and must support backing files, such as "qcow2".
+    pub fn create_overlay(&self, pool: &StoragePool, name: &str, format: &str) -> Result<StorageVol, Error> {
+        let config = self.get_config()?;
+        let path = self.get_path()?;
+        let backing_format = config.target
+            .as_ref()
+            .and_then(|t| t.format.clone())
+            .unwrap_or_else(|| "raw".to_string());
+        let capacity = match config.capacity {
+            Some(c) => c.to_bytes(MemoryUnit::Bytes)?,
+            None => self.get_info()?.capacity,
+        };
+        StorageVolBuilder::new(name)
+            .capacity(capacity, MemoryUnit::Bytes)
+            .format(format)
+            .backing_store(&path, &backing_format)
+            .create(pool, StorageVolCreateFlags::empty())
+    }
+}
@@ -0,0 +1,132 @@
+//! Fluent construction of storage volume definitions.
+//!
+//! ```
+//! # use new_libvirt::storage_vol::builder::StorageVolBuilder;
+//! # use new_libvirt::storage_vol::xml::MemoryUnit;
+//! let xml = StorageVolBuilder::new("vm1.qcow2")
+//!     .capacity(20, MemoryUnit::GiB)
+//!     .format("qcow2")
+//!     .lazy_refcounts()
+//!     .to_xml();
+//! ```
+
+use error::Error;
+use storage_pool::StoragePool;
+use storage_vol::StorageVol;
+use storage_vol::flags::StorageVolCreateFlags;
+use storage_vol::xml::*;
+
+/// Builds a `StorageVolConfig`.
+#[derive(Clone, Debug)]
+pub struct StorageVolBuilder {
+    config: StorageVolConfig,
+}
+
+impl StorageVolBuilder {
+    /// Start a volume named `name`. Set at least its `capacity()`
+    /// before creating it.
+    pub fn new(name: &str) -> StorageVolBuilder {
+        StorageVolBuilder { config: StorageVolConfig::new(name) }
+    }
+
+    fn target(&mut self) -> &mut VolumeTarget {
+        if self.config.target.is_none() {
+            self.config.target = Some(VolumeTarget::default());
+        }
+        self.config.target.as_mut().unwrap()
+    }
+
+    pub fn capacity(mut self, value: u64, unit: MemoryUnit) -> StorageVolBuilder {
+        self.config.capacity = Some(Memory::new(value, unit));
+        self
+    }
+
+    /// Space to allocate up front. Use 0 for a sparse volume.
+    pub fn allocation(mut self, value: u64, unit: MemoryUnit) -> StorageVolBuilder {
+        self.config.allocation = Some(Memory::new(value, unit));
+        self
+    }
+
+    /// Image format, such as "raw" or "qcow2".
+    pub fn format(mut self, format: &str) -> StorageVolBuilder {
+        self.target().format = Some(format.to_string());
+        self
+    }
+
+    /// Layer the volume on top of the image at `path`, which has the
+    /// given `format`. The volume must use a copy-on-write format such
+    /// as qcow2.
+    pub fn backing_store(mut self, path: &str, format: &str) -> StorageVolBuilder {
+        self.config.backing_store = Some(BackingStore {
+            path: Some(path.to_string()),
+            format: Some(format.to_string()),
+            permissions: None,
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    /// Mode and ownership of the volume file.
+    pub fn permissions(mut self, mode: u32, owner: u32, group: u32) -> StorageVolBuilder {
+        self.target().permissions = Some(Permissions {
+            mode: Some(format!("{:04o}", mode)),
+            owner: Some(owner),
+            group: Some(group),
+            label: None,
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    /// Encrypt the volume with LUKS, using the passphrase stored in the
+    /// secret `secret_uuid`.
+    pub fn encryption_luks(mut self, secret_uuid: &str) -> StorageVolBuilder {
+        self.target().encryption = Some(Encryption {
+            format: Some("luks".to_string()),
+            secret: Some(EncryptionSecret {
+                secret_type: Some("passphrase".to_string()),
+                uuid: Some(secret_uuid.to_string()),
+                usage: None,
+                extra: Extra::default(),
+            }),
+            extra: Extra::default(),
+        });
+        self
+    }
+
+    /// qcow2 compatibility level, "0.10" or "1.1".
+    pub fn compat(mut self, compat: &str) -> StorageVolBuilder {
+        self.target().compat = Some(compat.to_string());
+        self
+    }
+
+    /// Enable lazy refcounts, which needs qcow2 compat 1.1.
+    pub fn lazy_refcounts(mut self) -> StorageVolBuilder {
+        {
+            let target = self.target();
+            if target.compat.is_none() {
+                target.compat = Some("1.1".to_string());
+            }
+            target.features.push("lazy_refcounts".to_string());
+        }
+        self
+    }
+
+    pub fn nocow(mut self) -> StorageVolBuilder {
+        self.target().nocow = true;
+        self
+    }
+
+    pub fn build(self) -> StorageVolConfig {
+        self.config
+    }
+
+    pub fn to_xml(&self) -> String {
+        self.config.to_xml()
+    }
+
+    /// Create the volume in `pool`.
+    pub fn create(&self, pool: &StoragePool, flags: StorageVolCreateFlags) -> Result<StorageVol, Error> {
+        StorageVol::create_xml(pool, &self.config.to_xml(), flags)
+    }
+}
@@ -34,7 +34,7 @@ bitflags! {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum StorageVolType {
     File = 0,
     Block = 1,
@@ -66,6 +66,7 @@ bitflags! {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum StorageVolWipeAlgorithm {
     /// 1-pass, all zeroes
     Zero = 0,
@@ -90,3 +91,22 @@ pub enum StorageVolWipeAlgorithm {
     /// NB: this enum value will increase over time as new algorithms are added to the libvirt API. It reflects the last algorithm supported by this version of the libvirt API.
     Last = 10,
 }
+
+impl StorageVolWipeAlgorithm {
+    pub fn from_int(n: i32) -> Option<Self> {
+        match n {
+            0 => Some(StorageVolWipeAlgorithm::Zero),
+            1 => Some(StorageVolWipeAlgorithm::Nnsa),
+            2 => Some(StorageVolWipeAlgorithm::Dod),
+            3 => Some(StorageVolWipeAlgorithm::Bsi),
+            4 => Some(StorageVolWipeAlgorithm::Gutmann),
+            5 => Some(StorageVolWipeAlgorithm::Schneier),
+            6 => Some(StorageVolWipeAlgorithm::Pfitzner7),
+            7 => Some(StorageVolWipeAlgorithm::Pfitzner33),
+            8 => Some(StorageVolWipeAlgorithm::Random),
+            9 => Some(StorageVolWipeAlgorithm::Trim),
+            10 => Some(StorageVolWipeAlgorithm::Last),
+            _ => None,
+        }
+    }
+}
@@ -3,10 +3,17 @@ extern crate libvirt_sys as sys;
 use error::Error;
 
 use connect::Connect;
+use handle::Borrowed;
+use storage_pool::StoragePool;
 use stream::Stream;
 
+pub mod backing;
+pub mod builder;
 pub mod flags;
+pub mod wipe;
+pub mod xml;
 use storage_vol::flags::*;
+use storage_vol::xml::{StorageVolConfig, VolumeTarget, XmlModel};
 
 #[derive(Clone, Debug)]
 pub struct StorageVolInfo {
@@ -60,6 +67,22 @@ impl Drop for StorageVol {
     }
 }
 
+impl Clone for StorageVol {
+    /// Take a new reference to the same object.
+    fn clone(&self) -> StorageVol {
+        unsafe {
+            if sys::virStorageVolRef(self.as_ptr()) == -1 {
+                let e = Error::last_error();
+                panic!("Unable to add reference to StorageVol, code {}, message: {}", e.code, e.message)
+            }
+        }
+        StorageVol::new(self.as_ptr())
+    }
+}
+
+unsafe impl Send for StorageVol {}
+unsafe impl Sync for StorageVol {}
+
 impl StorageVol {
     pub fn new(ptr: sys::virStorageVolPtr) -> StorageVol {
         return StorageVol { ptr: Some(ptr) };
@@ -69,47 +92,71 @@ impl StorageVol {
         self.ptr.unwrap()
     }
 
-    pub fn get_connect(&self) -> Result<Connect, Error> {
+    /// The connection the object belongs to. libvirt doesn't take a
+    /// reference for the caller, so the connection is borrowed from
+    /// `self`; clone it to keep it longer.
+    pub fn get_connect<'a>(&'a self) -> Result<Borrowed<'a, Connect>, Error> {
         unsafe {
             let ptr = sys::virStorageVolGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::last_error());
             }
-            return Ok(Connect::new(ptr));
+            return Ok(Borrowed::new(Connect::new(ptr)));
         }
     }
 
-    // TODO: uncomment these once StoragePool is implemented
+    pub fn create_xml(pool: &StoragePool, xml: &str, flags: StorageVolCreateFlags) -> Result<StorageVol, Error> {
+        unsafe {
+            let ptr = sys::virStorageVolCreateXML(pool.as_ptr(), string_to_c_chars!(xml), flags.bits());
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(StorageVol::new(ptr));
+        }
+    }
 
-    // pub fn create_xml(pool: &StoragePool, xml: &str, flags: StorageVolCreateFlags) -> Result<StorageVol, Error> {
-    //     unsafe {
-    //         let ptr = sys::virStorageVolCreateXML(pool.as_ptr(), string_to_c_chars!(xml), flags.bits());
-    //         if ptr.is_null() {
-    //             return Err(Error::last_error());
-    //         }
-    //         return Ok(StorageVol::new(ptr));
-    //     }
-    // }
+    /// Create a volume in `pool` from `xml`, using the contents of
+    /// `vol` as its initial data.
+    pub fn create_xml_from(pool: &StoragePool, xml: &str, vol: &StorageVol, flags: StorageVolCreateFlags) -> Result<StorageVol, Error> {
+        unsafe {
+            let ptr = sys::virStorageVolCreateXMLFrom(pool.as_ptr(), string_to_c_chars!(xml), vol.as_ptr(), flags.bits());
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(StorageVol::new(ptr));
+        }
+    }
 
-    // pub fn create_xml_from(pool: &StoragePool, xml: &str, vol: &StorageVol, flags: StorageVolCreateFlags) -> Result<StorageVol, Error> {
-    //     unsafe {
-    //         let ptr = sys::virStorageVolCreateXMLFrom(pool.as_ptr(), string_to_c_chars!(xml), vol.as_ptr(), flags.bits());
-    //         if ptr.is_null() {
-    //             return Err(Error::last_error());
-    //         }
-    //         return Ok(StorageVol::new(ptr));
-    //     }
-    // }
+    /// Create a copy of this volume named `name` in `pool`.
+    ///
+    /// The copy keeps the capacity, format and format features of
+    /// this volume. It gets its own path and ownership from `pool`,
+    /// and doesn't share this volume's backing store or encryption.
+    pub fn clone_into(&self, pool: &StoragePool, name: &str) -> Result<StorageVol, Error> {
+        let source = self.get_config()?;
+        let mut config = StorageVolConfig::new(name);
+        config.capacity = source.capacity;
+        if let Some(t) = source.target {
+            config.target = Some(VolumeTarget {
+                format: t.format,
+                compat: t.compat,
+                nocow: t.nocow,
+                features: t.features,
+                ..VolumeTarget::default()
+            });
+        }
+        StorageVol::create_xml_from(pool, &config.to_xml(), self, StorageVolCreateFlags::empty())
+    }
 
-    // pub fn lookup_by_name(pool: &StoragePool, name: &str) -> Result<StorageVol, Error> {
-    //     unsafe {
-    //         let ptr = sys::virStorageVolLookupByName(pool.as_ptr(), string_to_c_chars!(name));
-    //         if ptr.is_null() {
-    //             return Err(Error::last_error());
-    //         }
-    //         return Ok(StorageVol::new(ptr));
-    //     }
-    // }
+    pub fn lookup_by_name(pool: &StoragePool, name: &str) -> Result<StorageVol, Error> {
+        unsafe {
+            let ptr = sys::virStorageVolLookupByName(pool.as_ptr(), string_to_c_chars!(name));
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(StorageVol::new(ptr));
+        }
+    }
 
     pub fn lookup_by_key(conn: &Connect, key: &str) -> Result<StorageVol, Error> {
         unsafe {
@@ -0,0 +1,150 @@
+//! Wiping volumes on a worker thread.
+//!
+//! `StorageVol::wipe()` and `wipe_pattern()` block until every pass
+//! has been written, which can take hours on large volumes.
+//! `StorageVol::wipe_in_background()` runs the wipe on its own thread
+//! and returns a `WipeHandle` to follow it.
+
+use error::{Error, ErrorDomain, ErrorNumber};
+use storage_vol::StorageVol;
+use storage_vol::flags::{StorageVolDeleteFlags, StorageVolType, StorageVolWipeAlgorithm};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A snapshot of a running wipe, see `WipeHandle::progress()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WipeProgress {
+    /// Bytes allocated to the volume.
+    pub allocation: u64,
+    /// Logical size of the volume in bytes.
+    pub capacity: u64,
+    /// Estimated fraction done, from 0.0 to 1.0, assuming the volume
+    /// grows from its initial allocation to its capacity while being
+    /// wiped. This is `None` for volumes which were fully allocated to
+    /// start with, such as logical volumes, and for a zero wipe of a
+    /// file volume, which libvirt does by truncating and re-extending
+    /// the file, so its allocation doesn't show any progress.
+    pub fraction: Option<f64>,
+}
+
+/// Follows a wipe started with `StorageVol::wipe_in_background()`.
+///
+/// Dropping the handle cancels the wipe as far as libvirt allows:
+/// libvirt can't interrupt a wipe pass once it has started, so the
+/// worker thread runs until the current call returns, but it skips
+/// deleting the volume afterwards and nobody waits for it.
+pub struct WipeHandle {
+    vol: StorageVol,
+    initial_allocation: u64,
+    estimable: bool,
+    result: Arc<Mutex<Option<Result<(), Error>>>>,
+    cancelled: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl WipeHandle {
+    /// Sample the volume allocation to estimate how far the wipe got.
+    pub fn progress(&self) -> Result<WipeProgress, Error> {
+        let info = self.vol.get_info()?;
+        let fraction = if self.estimable && info.capacity > self.initial_allocation {
+            let done = info.allocation.saturating_sub(self.initial_allocation);
+            Some((done as f64 / (info.capacity - self.initial_allocation) as f64).min(1.0))
+        } else {
+            None
+        };
+        Ok(WipeProgress {
+            allocation: info.allocation,
+            capacity: info.capacity,
+            fraction: fraction,
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result.lock().unwrap().is_some()
+    }
+
+    /// Block until the wipe, and the deletion if requested, is done.
+    pub fn wait(mut self) -> Result<(), Error> {
+        if let Some(t) = self.thread.take() {
+            if t.join().is_err() {
+                return Err(Error::new(ErrorNumber::InternalError,
+                                      ErrorDomain::Storage,
+                                      "wipe worker thread panicked"));
+            }
+        }
+        match self.result.lock().unwrap().take() {
+            Some(r) => r,
+            None => {
+                Err(Error::new(ErrorNumber::InternalError,
+                               ErrorDomain::Storage,
+                               "wipe worker thread exited without a result"))
+            }
+        }
+    }
+
+    /// Cancel the wipe, same as dropping the handle.
+    pub fn cancel(self) {}
+}
+
+impl Drop for WipeHandle {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+impl StorageVol {
+    /// Wipe the volume on a worker thread, with `algorithm` or the
+    /// default single pass of zeroes, and then delete it if `delete` is
+    /// set.
+    pub fn wipe_in_background(&self,
+                              algorithm: Option<StorageVolWipeAlgorithm>,
+                              delete: bool)
+                              -> Result<WipeHandle, Error> {
+        let info = self.get_info()?;
+        let zero = match algorithm {
+            None | Some(StorageVolWipeAlgorithm::Zero) => true,
+            Some(_) => false,
+        };
+        let estimable = !(zero && info.type_ == StorageVolType::File);
+        let vol = self.clone();
+        let worker = self.clone();
+        let result = Arc::new(Mutex::new(None));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thread_result = result.clone();
+        let thread_cancelled = cancelled.clone();
+        let thread = thread::Builder::new()
+            .name("libvirt-wipe".to_string())
+            .spawn(move || {
+                let vol = worker;
+                let mut ret = match algorithm {
+                    Some(a) => vol.wipe_pattern(a),
+                    None => vol.wipe(),
+                };
+                if ret.is_ok() && delete && !thread_cancelled.load(Ordering::SeqCst) {
+                    ret = vol.delete(StorageVolDeleteFlags::empty());
+                }
+                *thread_result.lock().unwrap() = Some(ret);
+            });
+        let thread = match thread {
+            Ok(t) => t,
+            Err(e) => {
+                // The closure, and the volume reference it owns, were
+                // dropped by the failed spawn.
+                return Err(Error::new(ErrorNumber::SystemError,
+                                      ErrorDomain::None,
+                                      format!("failed to start wipe thread: {}", e)));
+            }
+        };
+
+        Ok(WipeHandle {
+            vol: vol,
+            initial_allocation: info.allocation,
+            estimable: estimable,
+            result: result,
+            cancelled: cancelled,
+            thread: Some(thread),
+        })
+    }
+}
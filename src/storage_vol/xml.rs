@@ -0,0 +1,352 @@
+//! Typed model of the storage volume XML format.
+//!
+//! See: http://libvirt.org/formatstorage.html#StorageVol
+//!
+//! As with `domain::xml`, anything not modelled as a field is kept in
+//! the `extra` field of the enclosing struct.
+
+use error::Error;
+use storage_vol::StorageVol;
+use xmlutil::*;
+
+pub use domain::xml::{Memory, MemoryUnit};
+use domain::xml::{take_memory, push_memory};
+pub use storage_pool::xml::Permissions;
+pub use xmlutil::{Element, Extra, XmlModel};
+
+/// The secret used to unlock an encrypted volume, `<secret>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptionSecret {
+    /// Always "passphrase" for now.
+    pub secret_type: Option<String>,
+    pub uuid: Option<String>,
+    pub usage: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlModel for EncryptionSecret {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "secret")?;
+        Ok(EncryptionSecret {
+            secret_type: take_attr(&mut el, "type"),
+            uuid: take_attr(&mut el, "uuid"),
+            usage: take_attr(&mut el, "usage"),
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("secret");
+        set_attr(&mut el, "type", &self.secret_type);
+        set_attr(&mut el, "uuid", &self.uuid);
+        set_attr(&mut el, "usage", &self.usage);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Volume encryption, `<encryption>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encryption {
+    /// "luks", or "qcow" for the legacy qcow2 encryption.
+    pub format: Option<String>,
+    pub secret: Option<EncryptionSecret>,
+    pub extra: Extra,
+}
+
+impl XmlModel for Encryption {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "encryption")?;
+        Ok(Encryption {
+            format: take_attr(&mut el, "format"),
+            secret: take_model(&mut el, "secret")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("encryption");
+        set_attr(&mut el, "format", &self.format);
+        push_model(&mut el, &self.secret);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// Take `<features>` if it only holds flags such as
+/// `<lazy_refcounts/>`, returning their names. An empty `<features/>`
+/// is taken as well, and written back because `Extra` remembers it.
+fn take_features(el: &mut Element) -> Vec<String> {
+    let simple = match el.get_child("features") {
+        Some(f) => {
+            f.attributes.is_empty() &&
+            f.children.iter().all(|n| match n.as_element() {
+                Some(c) => c.attributes.is_empty() && c.children.is_empty(),
+                None => false,
+            })
+        }
+        None => false,
+    };
+    if !simple {
+        return Vec::new();
+    }
+    let features = take_child(el, "features").unwrap();
+    features.children
+        .iter()
+        .filter_map(|n| n.as_element())
+        .map(|c| c.name.clone())
+        .collect()
+}
+
+fn push_features(el: &mut Element, features: &[String], extra: &Extra) {
+    if !features.is_empty() || extra.had_child("features") {
+        let mut f = Element::new("features");
+        for name in features {
+            push_flag(&mut f, name, true);
+        }
+        push(el, f);
+    }
+}
+
+/// Where and how the volume is stored, `<target>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VolumeTarget {
+    pub path: Option<String>,
+    /// Image format, such as "raw", "qcow2" or "vmdk".
+    pub format: Option<String>,
+    pub permissions: Option<Permissions>,
+    /// qcow2 compatibility level, "0.10" or "1.1".
+    pub compat: Option<String>,
+    /// Disable copy-on-write on filesystems such as btrfs.
+    pub nocow: bool,
+    pub cluster_size: Option<Memory>,
+    /// Format features, such as "lazy_refcounts".
+    pub features: Vec<String>,
+    pub encryption: Option<Encryption>,
+    pub extra: Extra,
+}
+
+impl XmlModel for VolumeTarget {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "target")?;
+        Ok(VolumeTarget {
+            path: take_child_text(&mut el, "path"),
+            format: take_child_attr(&mut el, "format", "type"),
+            permissions: take_model(&mut el, "permissions")?,
+            compat: take_child_text(&mut el, "compat"),
+            nocow: take_flag(&mut el, "nocow"),
+            cluster_size: take_memory(&mut el, "clusterSize")?,
+            features: take_features(&mut el),
+            encryption: take_model(&mut el, "encryption")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("target");
+        push_text(&mut el, "path", &self.path);
+        push_child_attr(&mut el, "format", "type", &self.format);
+        push_model(&mut el, &self.permissions);
+        push_text(&mut el, "compat", &self.compat);
+        push_flag(&mut el, "nocow", self.nocow);
+        push_memory(&mut el, "clusterSize", &self.cluster_size);
+        push_features(&mut el, &self.features, &self.extra);
+        push_model(&mut el, &self.encryption);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// The image a copy-on-write volume is layered on, `<backingStore>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackingStore {
+    pub path: Option<String>,
+    pub format: Option<String>,
+    pub permissions: Option<Permissions>,
+    pub extra: Extra,
+}
+
+impl XmlModel for BackingStore {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "backingStore")?;
+        Ok(BackingStore {
+            path: take_child_text(&mut el, "path"),
+            format: take_child_attr(&mut el, "format", "type"),
+            permissions: take_model(&mut el, "permissions")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("backingStore");
+        push_text(&mut el, "path", &self.path);
+        push_child_attr(&mut el, "format", "type", &self.format);
+        push_model(&mut el, &self.permissions);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+/// A complete storage volume definition, the root `<volume>` element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageVolConfig {
+    /// "file", "block", "dir", "network", "netdir" or "ploop"; set by
+    /// libvirt in `get_xml_desc()` output.
+    pub vol_type: Option<String>,
+    pub name: String,
+    /// Unique key, set by libvirt.
+    pub key: Option<String>,
+    /// Logical size of the volume; bytes when no unit is given.
+    pub capacity: Option<Memory>,
+    /// Space to allocate up front; defaults to the whole capacity.
+    pub allocation: Option<Memory>,
+    /// Size of the image on disk, reported by libvirt.
+    pub physical: Option<Memory>,
+    pub target: Option<VolumeTarget>,
+    pub backing_store: Option<BackingStore>,
+    pub extra: Extra,
+}
+
+impl StorageVolConfig {
+    /// A minimal definition with the given name.
+    pub fn new(name: &str) -> StorageVolConfig {
+        StorageVolConfig {
+            vol_type: None,
+            name: name.to_string(),
+            key: None,
+            capacity: None,
+            allocation: None,
+            physical: None,
+            target: None,
+            backing_store: None,
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl XmlModel for StorageVolConfig {
+    fn from_element(mut el: Element) -> Result<Self, Error> {
+        expect_name(&el, "volume")?;
+        let name = match take_child_text(&mut el, "name") {
+            Some(n) => n,
+            None => return Err(xml_error("missing <name> in <volume>")),
+        };
+        Ok(StorageVolConfig {
+            vol_type: take_attr(&mut el, "type"),
+            name: name,
+            key: take_child_text(&mut el, "key"),
+            capacity: take_memory(&mut el, "capacity")?,
+            allocation: take_memory(&mut el, "allocation")?,
+            physical: take_memory(&mut el, "physical")?,
+            target: take_model(&mut el, "target")?,
+            backing_store: take_model(&mut el, "backingStore")?,
+            extra: Extra::take(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("volume");
+        set_attr(&mut el, "type", &self.vol_type);
+        push_text(&mut el, "name", &Some(&self.name));
+        push_text(&mut el, "key", &self.key);
+        push_memory(&mut el, "capacity", &self.capacity);
+        push_memory(&mut el, "allocation", &self.allocation);
+        push_memory(&mut el, "physical", &self.physical);
+        push_model(&mut el, &self.target);
+        push_model(&mut el, &self.backing_store);
+        self.extra.apply(&mut el);
+        el
+    }
+}
+
+impl StorageVol {
+    /// Fetch the XML description of the volume and parse it into a
+    /// `StorageVolConfig`.
+    pub fn get_config(&self) -> Result<StorageVolConfig, Error> {
+        StorageVolConfig::from_xml(&self.get_xml_desc()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that `xml` survives a parse/serialize round trip.
+    fn assert_round_trip(xml: &str) -> StorageVolConfig {
+        let config = StorageVolConfig::from_xml(xml).unwrap();
+        let written = config.to_xml();
+        assert_eq!(parse(&written).unwrap(), parse(xml).unwrap(), "round trip changed the XML:\n{}", written);
+        assert_eq!(StorageVolConfig::from_xml(&written).unwrap(), config);
+        config
+    }
+
+    #[test]
+    fn qcow2_volume() {
+        let config = assert_round_trip("<volume type='file'>
+  <name>overlay.qcow2</name>
+  <key>/var/lib/libvirt/images/overlay.qcow2</key>
+  <capacity unit='bytes'>21474836480</capacity>
+  <allocation unit='bytes'>200704</allocation>
+  <physical unit='bytes'>196616</physical>
+  <target>
+    <path>/var/lib/libvirt/images/overlay.qcow2</path>
+    <format type='qcow2'/>
+    <permissions>
+      <mode>0600</mode>
+      <owner>107</owner>
+      <group>107</group>
+      <label>system_u:object_r:virt_image_t:s0</label>
+    </permissions>
+    <timestamps>
+      <atime>1700000000.123456789</atime>
+      <mtime>1700000000.123456789</mtime>
+      <ctime>1700000000.123456789</ctime>
+    </timestamps>
+    <compat>1.1</compat>
+    <clusterSize unit='B'>65536</clusterSize>
+    <features>
+      <lazy_refcounts/>
+    </features>
+    <encryption format='luks'>
+      <secret type='passphrase' uuid='f52a81b2-424e-490c-823d-6bd4235bc572'/>
+      <cipher name='aes' size='256' mode='xts'/>
+    </encryption>
+  </target>
+  <backingStore>
+    <path>/var/lib/libvirt/images/base.qcow2</path>
+    <format type='qcow2'/>
+    <permissions>
+      <mode>0644</mode>
+      <owner>0</owner>
+      <group>0</group>
+    </permissions>
+  </backingStore>
+</volume>");
+        assert_eq!(config.capacity.as_ref().unwrap().to_bytes(MemoryUnit::Bytes).unwrap(), 21474836480);
+        let target = config.target.as_ref().unwrap();
+        assert_eq!(target.format.as_ref().unwrap(), "qcow2");
+        assert_eq!(target.compat.as_ref().unwrap(), "1.1");
+        assert_eq!(target.features, vec!["lazy_refcounts"]);
+        let encryption = target.encryption.as_ref().unwrap();
+        assert_eq!(encryption.format.as_ref().unwrap(), "luks");
+        assert_eq!(encryption.secret.as_ref().unwrap().uuid.as_ref().unwrap(),
+                   "f52a81b2-424e-490c-823d-6bd4235bc572");
+        assert_eq!(encryption.extra.children[0].name, "cipher");
+        let backing = config.backing_store.as_ref().unwrap();
+        assert_eq!(backing.path.as_ref().unwrap(), "/var/lib/libvirt/images/base.qcow2");
+        assert_eq!(backing.format.as_ref().unwrap(), "qcow2");
+    }
+
+    #[test]
+    fn empty_features_are_kept() {
+        let config = assert_round_trip("<volume>
+  <name>empty.qcow2</name>
+  <capacity unit='G'>1</capacity>
+  <target>
+    <format type='qcow2'/>
+    <features/>
+  </target>
+</volume>");
+        assert!(config.target.as_ref().unwrap().features.is_empty());
+    }
+}
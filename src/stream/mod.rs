@@ -1,9 +1,11 @@
 extern crate libc;
 extern crate libvirt_sys as sys;
-use error::Error;
+use error::{Error, ErrorDomain, ErrorNumber};
+
+use connect::Connect;
 
 pub mod flags;
-// use stream::flags::*;
+use stream::flags::*;
 
 #[derive(Debug)]
 pub struct Stream {
@@ -20,11 +22,28 @@ impl Drop for Stream {
     }
 }
 
+// A stream may move between threads, but libvirt doesn't allow two
+// threads to send or receive on it at once.
+unsafe impl Send for Stream {}
+
 impl Stream {
     pub fn new(ptr: sys::virStreamPtr) -> Stream {
         Stream { ptr: Some(ptr) }
     }
 
+    /// Create a new stream object on the given connection. The
+    /// stream is not yet associated with any data source; pass it to
+    /// an API such as `Domain::screenshot()` or `StorageVol::download()`.
+    pub fn create(conn: &Connect, flags: StreamFlags) -> Result<Stream, Error> {
+        unsafe {
+            let ptr = sys::virStreamNew(conn.as_ptr(), flags.bits());
+            if ptr.is_null() {
+                return Err(Error::last_error());
+            }
+            return Ok(Stream::new(ptr));
+        }
+    }
+
     pub fn as_ptr(&self) -> sys::virStreamPtr {
         self.ptr.unwrap()
     }
@@ -57,24 +76,58 @@ impl Stream {
         }
     }
 
-    pub fn send(&self, data: &str) -> Result<u32, Error> {
+    /// Write a series of bytes to the stream. Returns the number of
+    /// bytes actually written, which may be less than `data.len()`, or
+    /// `None` if the stream is non-blocking and isn't ready for more
+    /// data.
+    pub fn send(&self, data: &[u8]) -> Result<Option<usize>, Error> {
         unsafe {
-            let ret = sys::virStreamSend(self.as_ptr(), string_to_c_chars!(data), data.len());
-            if ret == -1 {
-                return Err(Error::last_error());
-            }
-            return Ok(ret as u32);
+            let ret = sys::virStreamSend(self.as_ptr(),
+                                         data.as_ptr() as *const libc::c_char,
+                                         data.len());
+            return Stream::transfer_result(ret);
         }
     }
 
-    pub fn recv(&self, size: usize) -> Result<String, Error> {
+    /// Read a series of bytes from the stream into `buf`. Returns the
+    /// number of bytes read, where `0` means the end of the stream was
+    /// reached, or `None` if the stream is non-blocking and no data is
+    /// available yet.
+    pub fn recv(&self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
         unsafe {
-            let mut data: [libc::c_char; 2048] = ['\0' as i8; 2048];
-            let ret = sys::virStreamRecv(self.as_ptr(), data.as_mut_ptr(), size);
-            if ret == -1 {
-                return Err(Error::last_error());
-            }
-            return Ok(c_chars_to_string!(data.as_ptr()));
+            let ret = sys::virStreamRecv(self.as_ptr(),
+                                         buf.as_mut_ptr() as *mut libc::c_char,
+                                         buf.len());
+            return Stream::transfer_result(ret);
+        }
+    }
+
+    fn transfer_result(ret: libc::c_int) -> Result<Option<usize>, Error> {
+        match ret {
+            // EAGAIN on a non-blocking stream
+            -2 => Ok(None),
+            n if n < 0 => Err(Error::last_error()),
+            n => Ok(Some(n as usize)),
+        }
+    }
+
+    /// Read from the stream until the end is reached, returning all
+    /// of the received data. The stream must be blocking.
+    pub fn recv_all(&self) -> Result<Vec<u8>, Error> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut buf = [0u8; 65536];
+        loop {
+            let n = match self.recv(&mut buf)? {
+                Some(0) => break,
+                Some(n) => n,
+                None => {
+                    return Err(Error::new(ErrorNumber::OperationInvalid,
+                                          ErrorDomain::Streams,
+                                          "recv_all() needs a blocking stream"));
+                }
+            };
+            data.extend_from_slice(&buf[..n]);
         }
+        return Ok(data);
     }
 }
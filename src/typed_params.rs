@@ -0,0 +1,77 @@
+extern crate libc;
+extern crate libvirt_sys as sys;
+
+use std::ptr;
+
+/// An owned array of typed parameters as returned by libvirt APIs
+/// such as `virDomainGetJobStats`. The array is released with
+/// `virTypedParamsFree` when dropped.
+pub struct TypedParams {
+    pub ptr: sys::virTypedParameterPtr,
+    pub nparams: libc::c_int,
+}
+
+impl Drop for TypedParams {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe {
+                sys::virTypedParamsFree(self.ptr, self.nparams);
+            }
+        }
+    }
+}
+
+impl TypedParams {
+    pub fn new() -> TypedParams {
+        TypedParams {
+            ptr: ptr::null_mut(),
+            nparams: 0,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i32> {
+        unsafe {
+            let mut value: libc::c_int = 0;
+            let ret = sys::virTypedParamsGetInt(self.ptr, self.nparams, string_to_c_chars!(name), &mut value);
+            if ret == 1 { Some(value as i32) } else { None }
+        }
+    }
+
+    pub fn get_uint(&self, name: &str) -> Option<u32> {
+        unsafe {
+            let mut value: libc::c_uint = 0;
+            let ret = sys::virTypedParamsGetUInt(self.ptr, self.nparams, string_to_c_chars!(name), &mut value);
+            if ret == 1 { Some(value as u32) } else { None }
+        }
+    }
+
+    pub fn get_ullong(&self, name: &str) -> Option<u64> {
+        unsafe {
+            let mut value: libc::c_ulonglong = 0;
+            let ret = sys::virTypedParamsGetULLong(self.ptr, self.nparams, string_to_c_chars!(name), &mut value);
+            if ret == 1 { Some(value as u64) } else { None }
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        unsafe {
+            let mut value: libc::c_int = 0;
+            let ret = sys::virTypedParamsGetBoolean(self.ptr, self.nparams, string_to_c_chars!(name), &mut value);
+            if ret == 1 { Some(value != 0) } else { None }
+        }
+    }
+
+    /// The returned string is copied; the parameter array keeps
+    /// ownership of the original.
+    pub fn get_string(&self, name: &str) -> Option<String> {
+        unsafe {
+            let mut value: *const libc::c_char = ptr::null();
+            let ret = sys::virTypedParamsGetString(self.ptr, self.nparams, string_to_c_chars!(name), &mut value);
+            if ret == 1 && !value.is_null() {
+                Some(c_chars_to_string!(value, nofree))
+            } else {
+                None
+            }
+        }
+    }
+}
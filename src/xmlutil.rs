@@ -0,0 +1,386 @@
+//! Helpers shared by the typed XML models, such as `domain::xml`.
+//!
+//! The models parse a document into an `xmltree::Element`, take out
+//! the attributes and children they know about, and keep whatever is
+//! left in an `Extra` so it is written back out unchanged.
+
+extern crate xmltree;
+
+use error::{Error, ErrorDomain, ErrorNumber};
+use std::fmt::Display;
+use std::mem;
+use std::str::FromStr;
+
+pub use self::xmltree::{Element, Namespace, XMLNode};
+
+/// Attributes and child elements not covered by a typed model. They
+/// are kept so that a parse/serialize round trip doesn't lose any
+/// information.
+///
+/// `Extra` also remembers where each child sat in the original
+/// element, so `apply` puts the unknown children back between the
+/// known ones instead of after them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Extra {
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Namespaces declared on the element itself, such as
+    /// `xmlns:qemu` on `<domain>`.
+    pub namespaces: Option<Namespace>,
+    order: Vec<Slot>,
+}
+
+/// The position of one child of the original element.
+#[derive(Clone, Debug, PartialEq)]
+enum Slot {
+    /// A child taken out by the model, by element name.
+    Known(String),
+    /// An index into `Extra::children`.
+    Unknown(usize),
+    /// A comment or processing instruction.
+    Other(XMLNode),
+}
+
+/// Target of the processing instructions which mark where the take_*
+/// helpers removed a child, until `Extra::take` records the position.
+const SLOT: &'static str = "xmlmodel-slot";
+
+impl Extra {
+    /// Collect everything still left in `el` after a model took out
+    /// the parts it understands.
+    pub fn take(el: Element) -> Extra {
+        let mut children = Vec::new();
+        let mut order = Vec::new();
+        for n in el.children {
+            match n {
+                XMLNode::Element(e) => {
+                    order.push(Slot::Unknown(children.len()));
+                    children.push(e);
+                }
+                XMLNode::ProcessingInstruction(ref target, Some(ref name)) if target == SLOT => {
+                    order.push(Slot::Known(name.clone()));
+                }
+                // Text is owned by the models which read it.
+                XMLNode::Text(_) => (),
+                other => order.push(Slot::Other(other)),
+            }
+        }
+        Extra {
+            attributes: el.attributes.into_iter().collect(),
+            children: children,
+            namespaces: el.namespaces,
+            order: order,
+        }
+    }
+
+    /// Write the preserved attributes and children back into `el`.
+    ///
+    /// The children the model pushed into `el` are merged with the
+    /// preserved ones in their original order. Children which weren't
+    /// in the original element follow the last sibling of the same
+    /// name, or go at the end.
+    pub fn apply(&self, el: &mut Element) {
+        for &(ref k, ref v) in &self.attributes {
+            el.attributes.insert(k.clone(), v.clone());
+        }
+        if self.namespaces.is_some() {
+            el.namespaces = self.namespaces.clone();
+        }
+        let mut pushed: Vec<Option<XMLNode>> = el.children.drain(..).map(Some).collect();
+        let mut used = vec![false; self.children.len()];
+        let mut children = Vec::new();
+        for slot in &self.order {
+            match *slot {
+                Slot::Known(ref name) => {
+                    let found = pushed.iter_mut().find(|n| match **n {
+                        Some(XMLNode::Element(ref e)) => e.name == *name,
+                        _ => false,
+                    });
+                    if let Some(n) = found.and_then(|n| n.take()) {
+                        children.push(n);
+                    }
+                }
+                Slot::Unknown(i) => {
+                    if let Some(c) = self.children.get(i) {
+                        used[i] = true;
+                        children.push(XMLNode::Element(c.clone()));
+                    }
+                }
+                Slot::Other(ref n) => children.push(n.clone()),
+            }
+        }
+        for n in pushed.into_iter().filter_map(|n| n) {
+            let after = match n {
+                XMLNode::Element(ref e) => children.iter().rposition(|c| match *c {
+                    XMLNode::Element(ref c) => c.name == e.name,
+                    _ => false,
+                }),
+                _ => None,
+            };
+            match after {
+                Some(i) => children.insert(i + 1, n),
+                None => children.push(n),
+            }
+        }
+        for (i, c) in self.children.iter().enumerate() {
+            if !used[i] {
+                children.push(XMLNode::Element(c.clone()));
+            }
+        }
+        el.children = children;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty()
+    }
+
+    /// Whether the original element had a child `name` which the model
+    /// took out.
+    pub fn had_child(&self, name: &str) -> bool {
+        self.order.iter().any(|s| match *s {
+            Slot::Known(ref n) => n == name,
+            _ => false,
+        })
+    }
+}
+
+/// A typed view of an XML element.
+pub trait XmlModel: Sized {
+    /// Build the model from `el`, keeping unknown content.
+    fn from_element(el: Element) -> Result<Self, Error>;
+    /// Serialize the model back into an element.
+    fn to_element(&self) -> Element;
+
+    /// Parse the model from an XML document.
+    fn from_xml(xml: &str) -> Result<Self, Error> {
+        Self::from_element(parse(xml)?)
+    }
+
+    /// Serialize the model into an indented XML document.
+    fn to_xml(&self) -> String {
+        write(&self.to_element())
+    }
+}
+
+pub fn xml_error<S: Into<String>>(message: S) -> Error {
+    Error::new(ErrorNumber::XmlError, ErrorDomain::Xml, message)
+}
+
+pub fn parse(xml: &str) -> Result<Element, Error> {
+    let mut el = Element::parse(xml.as_bytes()).map_err(|e| xml_error(format!("XML parse error: {}", e)))?;
+    own_namespaces(&mut el, &Namespace::empty());
+    Ok(el)
+}
+
+/// The parser gives every element all the namespaces in scope. Keep
+/// only those `el` and its descendants declare themselves, so each
+/// declaration is written back once, on the element it came from.
+fn own_namespaces(el: &mut Element, parent: &Namespace) {
+    let all = el.namespaces.take().unwrap_or_else(Namespace::empty);
+    for n in &mut el.children {
+        if let XMLNode::Element(ref mut c) = *n {
+            own_namespaces(c, &all);
+        }
+    }
+    let own = Namespace(all.0
+        .iter()
+        .filter(|&(prefix, uri)| parent.get(prefix) != Some(&uri[..]))
+        .map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+        .collect());
+    if !own.is_essentially_empty() {
+        el.namespaces = Some(own);
+    }
+}
+
+pub fn write(el: &Element) -> String {
+    let config = xmltree::EmitterConfig::new()
+        .perform_indent(true)
+        .write_document_declaration(false);
+    let mut out: Vec<u8> = Vec::new();
+    el.write_with_config(&mut out, config).expect("writing XML to memory cannot fail");
+    String::from_utf8(out).expect("XML writer produced invalid UTF-8")
+}
+
+/// Check that `el` is named `name`.
+pub fn expect_name(el: &Element, name: &str) -> Result<(), Error> {
+    if el.name != name {
+        return Err(xml_error(format!("expected <{}> element, found <{}>", name, el.name)));
+    }
+    Ok(())
+}
+
+pub fn take_attr(el: &mut Element, name: &str) -> Option<String> {
+    el.attributes.remove(name)
+}
+
+pub fn take_attr_parse<T: FromStr>(el: &mut Element, name: &str) -> Result<Option<T>, Error> {
+    match el.attributes.remove(name) {
+        Some(v) => {
+            v.trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| xml_error(format!("invalid value '{}' for attribute '{}' of <{}>", v, name, el.name)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Take a "yes"/"no" or "on"/"off" attribute.
+pub fn take_attr_bool(el: &mut Element, name: &str) -> Result<Option<bool>, Error> {
+    match el.attributes.remove(name) {
+        Some(v) => {
+            match v.as_str() {
+                "yes" | "on" => Ok(Some(true)),
+                "no" | "off" => Ok(Some(false)),
+                _ => Err(xml_error(format!("invalid value '{}' for attribute '{}' of <{}>", v, name, el.name))),
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Remove the child node at `index`, leaving a marker so that
+/// `Extra::take` knows where it was.
+fn remove_child(el: &mut Element, index: usize) -> Option<Element> {
+    let name = match el.children[index] {
+        XMLNode::Element(ref e) => e.name.clone(),
+        _ => return None,
+    };
+    match mem::replace(&mut el.children[index], XMLNode::ProcessingInstruction(SLOT.to_string(), Some(name))) {
+        XMLNode::Element(e) => Some(e),
+        _ => None,
+    }
+}
+
+pub fn take_child(el: &mut Element, name: &str) -> Option<Element> {
+    let index = el.children.iter().position(|n| match *n {
+        XMLNode::Element(ref e) => e.name == name,
+        _ => false,
+    });
+    index.and_then(|i| remove_child(el, i))
+}
+
+pub fn take_children(el: &mut Element, name: &str) -> Vec<Element> {
+    let mut taken = Vec::new();
+    while let Some(c) = take_child(el, name) {
+        taken.push(c);
+    }
+    taken
+}
+
+/// Take a child element which carries nothing but its presence, such
+/// as `<readonly/>`. Children with attributes or content are left in
+/// place so they end up in `Extra`.
+pub fn take_flag(el: &mut Element, name: &str) -> bool {
+    let index = el.children.iter().position(|n| match *n {
+        XMLNode::Element(ref e) => e.name == name && e.attributes.is_empty() && e.children.is_empty(),
+        _ => false,
+    });
+    index.and_then(|i| remove_child(el, i)).is_some()
+}
+
+/// The text content of `el`, exactly as written.
+pub fn text(el: &Element) -> Option<String> {
+    el.get_text().map(|t| t.into_owned())
+}
+
+/// Take the text content out of `el`, leaving its child elements.
+pub fn take_text(el: &mut Element) -> Option<String> {
+    let t = text(el);
+    el.children.retain(|n| match *n {
+        XMLNode::Text(_) => false,
+        _ => true,
+    });
+    t
+}
+
+pub fn take_child_text(el: &mut Element, name: &str) -> Option<String> {
+    take_child(el, name).and_then(|c| text(&c))
+}
+
+pub fn take_child_parse<T: FromStr>(el: &mut Element, name: &str) -> Result<Option<T>, Error> {
+    match take_child_text(el, name) {
+        Some(v) => v.trim().parse().map(Some).map_err(|_| xml_error(format!("invalid value '{}' for <{}>", v, name))),
+        None => Ok(None),
+    }
+}
+
+/// Take a child element and convert it with its model.
+pub fn take_model<T: XmlModel>(el: &mut Element, name: &str) -> Result<Option<T>, Error> {
+    match take_child(el, name) {
+        Some(c) => T::from_element(c).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Take all child elements with a given name and convert them.
+pub fn take_models<T: XmlModel>(el: &mut Element, name: &str) -> Result<Vec<T>, Error> {
+    take_children(el, name).into_iter().map(T::from_element).collect()
+}
+
+pub fn set_attr<T: Display>(el: &mut Element, name: &str, value: &Option<T>) {
+    if let Some(ref v) = *value {
+        el.attributes.insert(name.to_string(), v.to_string());
+    }
+}
+
+pub fn set_attr_bool(el: &mut Element, name: &str, value: Option<bool>, yes: &str, no: &str) {
+    if let Some(v) = value {
+        el.attributes.insert(name.to_string(), (if v { yes } else { no }).to_string());
+    }
+}
+
+pub fn push(el: &mut Element, child: Element) {
+    el.children.push(XMLNode::Element(child));
+}
+
+pub fn push_text<T: Display>(el: &mut Element, name: &str, value: &Option<T>) {
+    if let Some(ref v) = *value {
+        push(el, text_element(name, v));
+    }
+}
+
+pub fn push_flag(el: &mut Element, name: &str, value: bool) {
+    if value {
+        push(el, Element::new(name));
+    }
+}
+
+pub fn push_model<T: XmlModel>(el: &mut Element, value: &Option<T>) {
+    if let Some(ref v) = *value {
+        push(el, v.to_element());
+    }
+}
+
+pub fn push_models<T: XmlModel>(el: &mut Element, values: &[T]) {
+    for v in values {
+        push(el, v.to_element());
+    }
+}
+
+pub fn text_element<T: Display>(name: &str, value: T) -> Element {
+    let mut el = Element::new(name);
+    el.children.push(XMLNode::Text(value.to_string()));
+    el
+}
+
+/// Take the single attribute `attr` of a child element `name`, such
+/// as `<mac address='...'/>`. Children with anything else are left in
+/// place so they end up in `Extra`.
+pub fn take_child_attr(el: &mut Element, name: &str, attr: &str) -> Option<String> {
+    let index = el.children.iter().position(|n| match *n {
+        XMLNode::Element(ref e) => {
+            e.name == name && e.attributes.len() == 1 && e.attributes.contains_key(attr) && e.children.is_empty()
+        }
+        _ => false,
+    });
+    index.and_then(|i| remove_child(el, i)).and_then(|mut c| c.attributes.remove(attr))
+}
+
+pub fn push_child_attr<T: Display>(el: &mut Element, name: &str, attr: &str, value: &Option<T>) {
+    if value.is_some() {
+        let mut c = Element::new(name);
+        set_attr(&mut c, attr, value);
+        push(el, c);
+    }
+}
@@ -0,0 +1,118 @@
+extern crate new_libvirt;
+
+use new_libvirt::connect::Connect;
+use new_libvirt::domain::Domain;
+use new_libvirt::error::Error;
+use new_libvirt::storage_pool::StoragePool;
+use new_libvirt::storage_vol::StorageVol;
+use std::sync::Arc;
+use std::thread;
+
+const THREADS: usize = 8;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn handles_are_send_and_sync() {
+    assert_send_sync::<Connect>();
+    assert_send_sync::<Domain>();
+    assert_send_sync::<StoragePool>();
+    assert_send_sync::<StorageVol>();
+}
+
+#[test]
+fn shared_connection() {
+    let conn = Arc::new(Connect::open("test:///default").unwrap());
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let conn = conn.clone();
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    let dom = Domain::lookup_by_name(&conn, "test").unwrap();
+                    assert_eq!(dom.get_name().unwrap(), "test");
+                    assert!(dom.get_info().unwrap().nr_virt_cpu > 0);
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn cloned_domain_per_thread() {
+    let conn = Connect::open("test:///default").unwrap();
+    let dom = Domain::lookup_by_name(&conn, "test").unwrap();
+    let uuid = dom.get_uuid_string().unwrap();
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let dom = dom.clone();
+            let uuid = uuid.clone();
+            thread::spawn(move || {
+                assert_eq!(dom.get_uuid_string().unwrap(), uuid);
+                // The connection is borrowed from the domain, which keeps
+                // it alive after the original handle is dropped.
+                let conn = dom.get_connect().unwrap();
+                assert_eq!(Domain::lookup_by_uuid_string(&conn, &uuid).unwrap().get_name().unwrap(), "test");
+            })
+        })
+        .collect();
+    drop(dom);
+    drop(conn);
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn shared_storage_pool() {
+    let conn = Connect::open("test:///default").unwrap();
+    let pool = Arc::new(StoragePool::lookup_by_name(&conn, "default-pool").unwrap());
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    assert_eq!(pool.get_name().unwrap(), "default-pool");
+                    pool.get_info().unwrap();
+                    pool.list_volumes().unwrap();
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn errors_are_per_thread() {
+    let conn = Arc::new(Connect::open("test:///default").unwrap());
+    let threads: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let conn = conn.clone();
+            thread::spawn(move || {
+                let name = format!("missing-{}", i);
+                for _ in 0..50 {
+                    let err = Domain::lookup_by_name(&conn, &name).err().expect("lookup succeeded");
+                    assert!(err.message.contains(&name), "{} reported {}", name, err.message);
+                    Domain::lookup_by_name(&conn, "test").unwrap();
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn last_error_is_cleared() {
+    let conn = Connect::open("test:///default").unwrap();
+    let err = Domain::lookup_by_name(&conn, "missing").err().expect("lookup succeeded");
+    assert!(err.message.contains("missing"));
+    // Reading the error cleared it, so a later failure without an
+    // error of its own doesn't report a stale one.
+    assert!(!Error::last_error().message.contains("missing"));
+}
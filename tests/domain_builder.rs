@@ -0,0 +1,78 @@
+extern crate new_libvirt;
+
+use new_libvirt::connect::Connect;
+use new_libvirt::domain::Domain;
+use new_libvirt::domain::builder::{DomainBuilder, Hypervisor};
+use new_libvirt::domain::flags::XmlFlags;
+use new_libvirt::domain::xml::{DomainConfig, XmlModel};
+
+fn vm(name: &str, hypervisor: Hypervisor) -> DomainBuilder {
+    DomainBuilder::new(name)
+        .hypervisor(hypervisor)
+        .memory_mib(2048)
+        .vcpus(2)
+        .disk_from_path("/var/lib/libvirt/images/vm.qcow2", Some("qcow2"))
+        .network_interface("default")
+        .virtio_console()
+}
+
+#[test]
+fn define_on_test_driver() {
+    let conn = Connect::open("test:///default").unwrap();
+    let dom = vm("builder-test", Hypervisor::Test)
+        .vnc_graphics()
+        .define(&conn)
+        .unwrap();
+    assert_eq!(dom.get_name().unwrap(), "builder-test");
+
+    let config = dom.get_config(XmlFlags::empty()).unwrap();
+    assert_eq!(config.domain_type, "test");
+    assert_eq!(config.memory.unwrap().to_kib().unwrap(), 2048 * 1024);
+    assert_eq!(config.vcpu.unwrap().count, 2);
+    assert_eq!(config.devices.disks.len(), 1);
+    assert_eq!(config.devices.interfaces.len(), 1);
+    assert_eq!(config.devices.graphics[0].graphics_type.as_ref().unwrap(), "vnc");
+
+    dom.undefine().unwrap();
+    assert!(Domain::lookup_by_name(&conn, "builder-test").is_err());
+}
+
+#[test]
+fn kvm_and_qemu_xml() {
+    for &(hypervisor, domain_type) in &[(Hypervisor::Kvm, "kvm"), (Hypervisor::Qemu, "qemu")] {
+        let xml = vm("vm1", hypervisor).vnc_graphics().to_xml().unwrap();
+        let config = DomainConfig::from_xml(&xml).unwrap();
+        assert_eq!(config.domain_type, domain_type);
+        assert_eq!(config.os.unwrap().os_type.value, "hvm");
+
+        let disk = &config.devices.disks[0];
+        assert_eq!(disk.driver.as_ref().unwrap().driver_type.as_ref().unwrap(), "qcow2");
+        assert_eq!(disk.target.as_ref().unwrap().dev.as_ref().unwrap(), "vda");
+        assert_eq!(config.devices.interfaces[0].model.as_ref().unwrap(), "virtio");
+        let console = &config.devices.consoles[0];
+        assert_eq!(console.target.as_ref().unwrap().target_type.as_ref().unwrap(), "virtio");
+        assert_eq!(config.devices.graphics[0].autoport, Some(true));
+    }
+}
+
+#[test]
+fn lxc_xml() {
+    let xml = vm("ct1", Hypervisor::Lxc).init("/bin/sh").to_xml().unwrap();
+    let config = DomainConfig::from_xml(&xml).unwrap();
+    assert_eq!(config.domain_type, "lxc");
+    let os = config.os.unwrap();
+    assert_eq!(os.os_type.value, "exe");
+    assert_eq!(os.init.unwrap(), "/bin/sh");
+
+    assert!(config.devices.disks.is_empty());
+    let fs = &config.devices.filesystems[0];
+    assert_eq!(fs.fs_type.as_ref().unwrap(), "file");
+    assert_eq!(fs.target_dir.as_ref().unwrap(), "/");
+    let console = &config.devices.consoles[0];
+    assert_eq!(console.target.as_ref().unwrap().target_type.as_ref().unwrap(), "lxc");
+}
+
+#[test]
+fn lxc_rejects_vnc() {
+    assert!(vm("ct1", Hypervisor::Lxc).vnc_graphics().build().is_err());
+}
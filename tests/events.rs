@@ -0,0 +1,39 @@
+extern crate new_libvirt;
+
+use new_libvirt::connect::Connect;
+use new_libvirt::event;
+use new_libvirt::storage_pool::StoragePool;
+use new_libvirt::storage_pool::flags::{StoragePoolCreateFlags, StoragePoolEventId};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Run the event loop until `calls` reaches `count`.
+fn run_until(calls: &AtomicUsize, count: usize) {
+    while calls.load(Ordering::SeqCst) < count {
+        event::event_run_default_impl().unwrap();
+    }
+}
+
+#[test]
+fn panicking_callbacks_are_contained() {
+    event::event_register_default_impl().unwrap();
+    let conn = Connect::open("test:///default").unwrap();
+    let pool = StoragePool::lookup_by_name(&conn, "default-pool").unwrap();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let registration = conn
+        .storage_pool_event_register_any(Some(&pool), StoragePoolEventId::Lifecycle, move |_, p, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            panic!("callback for {} failed", p.get_name().unwrap());
+        })
+        .unwrap();
+
+    pool.destroy().unwrap();
+    run_until(&calls, 1);
+    // The panic was contained, so the callback keeps being called.
+    pool.create(StoragePoolCreateFlags::empty()).unwrap();
+    run_until(&calls, 2);
+    assert!(pool.is_active().unwrap());
+    drop(registration);
+}